@@ -1,8 +1,11 @@
 #![cfg(test)]
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+};
 
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Coin, CosmosMsg, DepsMut, Empty, Response, StdError, WasmMsg,
+    attr, coins, from_json, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Empty,
+    Response, StdError, Uint128, WasmMsg,
 };
 
 use cw721::{
@@ -12,7 +15,8 @@ use cw721::{
 use cw_ownable::OwnershipError;
 
 use crate::{
-    ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg, MinterResponse, QueryMsg,
+    ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg, MintMsg, MinterResponse,
+    QueryMsg, TransferMode, MAX_BATCH_SIZE,
 };
 
 const MINTER: &str = "merlin";
@@ -306,6 +310,380 @@ fn burning() {
     assert!(tokens.tokens.is_empty());
 }
 
+#[test]
+fn updating_nft_info() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = "petrify".to_string();
+    let mint_msg = ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("medusa"),
+        token_uri: Some("ipfs://placeholder".to_string()),
+        extension: None,
+    };
+    let allowed = mock_info(MINTER, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg)
+        .unwrap();
+
+    let update_msg = ExecuteMsg::UpdateNftInfo {
+        token_id: token_id.clone(),
+        token_uri: Some("ipfs://revealed".to_string()),
+        extension: Some(Empty {}),
+    };
+
+    // random cannot update, not even the token owner
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("medusa", &[]),
+            update_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+
+    // minter can update
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            update_msg.clone(),
+        )
+        .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_nft_info"),
+            attr("sender", MINTER),
+            attr("token_id", token_id.clone()),
+        ]
+    );
+    let info = contract.nft_info(deps.as_ref(), token_id.clone()).unwrap();
+    assert_eq!(
+        info,
+        NftInfoResponse::<Extension> {
+            token_uri: Some("ipfs://revealed".to_string()),
+            extension: Some(Empty {}),
+        }
+    );
+
+    // unknown nft can't be updated
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: "unknown".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Std(StdError::NotFound { .. })));
+
+    // random cannot freeze
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            ExecuteMsg::FreezeMetadata {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+
+    // once frozen, the metadata can't be changed anymore
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::FreezeMetadata {},
+        )
+        .unwrap();
+    assert!(contract.is_metadata_frozen(deps.as_ref().storage).unwrap());
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), allowed, update_msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::MetadataFrozen {});
+}
+
+#[test]
+fn batch_minting_transferring_and_burning() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    let mints: Vec<MintMsg<Extension>> = (0..3)
+        .map(|i| MintMsg {
+            token_id: format!("egg{i}"),
+            owner: "venus".to_string(),
+            token_uri: None,
+            extension: None,
+        })
+        .collect();
+
+    // random cannot mint
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            ExecuteMsg::BatchMint {
+                mints: mints.clone(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::BatchMint {
+                mints: mints.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(3, contract.num_tokens(deps.as_ref()).unwrap().count);
+
+    // a batch fails as a whole if a single token is claimed
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::BatchMint {
+                mints: mints[2..].to_vec(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Claimed {});
+
+    // batches must not be empty or too large
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::BatchMint { mints: vec![] },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBatchSize {
+            max: MAX_BATCH_SIZE
+        }
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::BatchBurn {
+                token_ids: vec!["egg0".to_string(); MAX_BATCH_SIZE + 1],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBatchSize {
+            max: MAX_BATCH_SIZE
+        }
+    );
+
+    // only the owner can transfer
+    let transfer_msg = ExecuteMsg::BatchTransfer {
+        recipient: "mars".to_string(),
+        token_ids: vec!["egg0".to_string(), "egg1".to_string()],
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+    let tokens = contract
+        .tokens(deps.as_ref(), "mars".to_string(), None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["egg0".to_string(), "egg1".to_string()]);
+
+    // venus can't burn the tokens of mars
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::BatchBurn {
+                token_ids: vec!["egg1".to_string(), "egg2".to_string()],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mars", &[]),
+            ExecuteMsg::BatchBurn {
+                token_ids: vec!["egg0".to_string(), "egg1".to_string()],
+            },
+        )
+        .unwrap();
+    assert_eq!(1, contract.num_tokens(deps.as_ref()).unwrap().count);
+}
+
+#[test]
+fn transfer_modes() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+    let venus = mock_info("venus", &[]);
+
+    let mint_msg = ExecuteMsg::Mint {
+        token_id: "melt".to_string(),
+        owner: "venus".to_string(),
+        token_uri: None,
+        extension: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+    let transfer_msg = |recipient: &str| ExecuteMsg::TransferNft {
+        recipient: recipient.to_string(),
+        token_id: "melt".to_string(),
+    };
+    let approve_msg = ExecuteMsg::Approve {
+        spender: "random".to_string(),
+        token_id: "melt".to_string(),
+        expires: None,
+    };
+
+    // only the owner can set the mode
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus.clone(),
+            ExecuteMsg::SetTransferMode {
+                mode: TransferMode::Soulbound,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+
+    // soulbound tokens can't be transferred or approved, not even by the minter
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::SetTransferMode {
+                mode: TransferMode::Soulbound,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        contract.transfer_mode(deps.as_ref().storage).unwrap(),
+        TransferMode::Soulbound
+    );
+    for (info, msg) in [
+        (venus.clone(), transfer_msg("mars")),
+        (minter.clone(), transfer_msg("mars")),
+        (venus.clone(), approve_msg.clone()),
+        (
+            venus.clone(),
+            ExecuteMsg::ApproveAll {
+                operator: "random".to_string(),
+                expires: None,
+            },
+        ),
+    ] {
+        let err = contract
+            .execute(deps.as_mut(), mock_env(), info, msg)
+            .unwrap_err();
+        assert_eq!(err, ContractError::NonTransferable {});
+    }
+
+    // only the minter can transfer
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::SetTransferMode {
+                mode: TransferMode::MinterOnly,
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus.clone(),
+            transfer_msg("mars"),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NonTransferable {});
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus.clone(),
+            approve_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NonTransferable {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            transfer_msg("mars"),
+        )
+        .unwrap();
+    let owner = contract
+        .owner_of(deps.as_ref(), mock_env(), "melt".to_string(), false)
+        .unwrap();
+    assert_eq!(owner.owner, "mars");
+
+    // back to normal
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::SetTransferMode {
+                mode: TransferMode::Transferable,
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mars", &[]),
+            transfer_msg("venus"),
+        )
+        .unwrap();
+}
+
 #[test]
 fn transferring_nft() {
     let mut deps = mock_dependencies();
@@ -907,12 +1285,18 @@ fn test_remove_withdraw_address() {
 
 #[test]
 fn test_withdraw_funds() {
-    let mut deps = mock_dependencies();
+    let mut deps = mock_dependencies_with_balance(&coins(100, "uark"));
     let contract = setup_contract(deps.as_mut());
+    let env = mock_env();
 
     // no withdraw address set
     let err = contract
-        .withdraw_funds(deps.as_mut().storage, &Coin::new(100, "uark"))
+        .withdraw_funds(
+            deps.as_mut(),
+            &env,
+            &Addr::unchecked("other"),
+            &Coin::new(100, "uark"),
+        )
         .unwrap_err();
     assert_eq!(err, ContractError::NoWithdrawAddress {});
 
@@ -920,9 +1304,171 @@ fn test_withdraw_funds() {
     contract
         .set_withdraw_address(deps.as_mut(), &Addr::unchecked(MINTER), "foo".to_string())
         .unwrap();
+    let res = contract
+        .withdraw_funds(
+            deps.as_mut(),
+            &env,
+            &Addr::unchecked("other"),
+            &Coin::new(100, "uark"),
+        )
+        .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "foo".to_string(),
+            amount: coins(100, "uark"),
+        })
+    );
+
+    // more than the balance
+    let err = contract
+        .withdraw_funds(
+            deps.as_mut(),
+            &env,
+            &Addr::unchecked("other"),
+            &Coin::new(101, "uark"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientFunds {
+            available: Coin::new(100, "uark")
+        }
+    );
+}
+
+#[test]
+fn test_withdraw_restricted() {
+    let mut deps = mock_dependencies_with_balance(&coins(100, "uark"));
+    let contract = setup_contract(deps.as_mut());
+    let env = mock_env();
+    contract
+        .set_withdraw_address(deps.as_mut(), &Addr::unchecked(MINTER), "foo".to_string())
+        .unwrap();
+
+    // other cant restrict
+    let err = contract
+        .set_withdraw_restricted(deps.as_mut().storage, &Addr::unchecked("other"), true)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+
+    contract
+        .set_withdraw_restricted(deps.as_mut().storage, &Addr::unchecked(MINTER), true)
+        .unwrap();
+
+    // only the owner and the withdraw address can withdraw now
+    let err = contract
+        .withdraw_funds(
+            deps.as_mut(),
+            &env,
+            &Addr::unchecked("other"),
+            &Coin::new(10, "uark"),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::WithdrawUnauthorized {});
+    contract
+        .withdraw_funds(
+            deps.as_mut(),
+            &env,
+            &Addr::unchecked(MINTER),
+            &Coin::new(10, "uark"),
+        )
+        .unwrap();
+    contract
+        .withdraw_funds(
+            deps.as_mut(),
+            &env,
+            &Addr::unchecked("foo"),
+            &Coin::new(10, "uark"),
+        )
+        .unwrap();
+
+    // lifting the restriction
+    contract
+        .set_withdraw_restricted(deps.as_mut().storage, &Addr::unchecked(MINTER), false)
+        .unwrap();
+    contract
+        .withdraw_funds(
+            deps.as_mut(),
+            &env,
+            &Addr::unchecked("other"),
+            &Coin::new(10, "uark"),
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_withdraw_all_excludes_reserved_funds() {
+    let mut deps = mock_dependencies_with_balance(&coins(100, "uark"));
+    let contract = setup_contract(deps.as_mut());
+    let env = mock_env();
     contract
-        .withdraw_funds(deps.as_mut().storage, &Coin::new(100, "uark"))
+        .set_withdraw_address(deps.as_mut(), &Addr::unchecked(MINTER), "foo".to_string())
         .unwrap();
+
+    contract
+        .reserve_funds(deps.as_mut().storage, "uark", Uint128::new(30))
+        .unwrap();
+    assert_eq!(
+        contract
+            .withdrawable_funds(deps.as_ref(), &env, "uark")
+            .unwrap(),
+        Coin::new(70, "uark")
+    );
+
+    // reserved funds can't be withdrawn
+    let err = contract
+        .withdraw_funds(
+            deps.as_mut(),
+            &env,
+            &Addr::unchecked("other"),
+            &Coin::new(71, "uark"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientFunds {
+            available: Coin::new(70, "uark")
+        }
+    );
+
+    let res = contract
+        .withdraw_all(deps.as_mut(), &env, &Addr::unchecked("other"), "uark")
+        .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "foo".to_string(),
+            amount: coins(70, "uark"),
+        })
+    );
+
+    // released funds can be withdrawn again
+    contract
+        .release_funds(deps.as_mut().storage, "uark", Uint128::new(30))
+        .unwrap();
+    assert!(!contract.reserved_funds.has(deps.as_ref().storage, "uark"));
+    let res = contract
+        .withdraw_all(deps.as_mut(), &env, &Addr::unchecked("other"), "uark")
+        .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "foo".to_string(),
+            amount: coins(100, "uark"),
+        })
+    );
+
+    // nothing to withdraw
+    let err = contract
+        .withdraw_all(deps.as_mut(), &env, &Addr::unchecked("other"), "uother")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientFunds {
+            available: Coin::new(0, "uother")
+        }
+    );
 }
 
 #[test]
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError};
 use cw_ownable::OwnershipError;
 use thiserror::Error;
 
@@ -22,6 +22,21 @@ pub enum ContractError {
     #[error("Approval not found for: {spender}")]
     ApprovalNotFound { spender: String },
 
+    #[error("A batch must contain between 1 and {max} tokens")]
+    InvalidBatchSize { max: usize },
+
+    #[error("The token can't be transferred")]
+    NonTransferable {},
+
+    #[error("The metadata is frozen")]
+    MetadataFrozen {},
+
     #[error("No withdraw address set")]
     NoWithdrawAddress {},
+
+    #[error("Only the owner or the withdraw address can withdraw funds")]
+    WithdrawUnauthorized {},
+
+    #[error("Insufficient funds, available: {available}")]
+    InsufficientFunds { available: Coin },
 }
@@ -3,14 +3,18 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use cosmwasm_std::{
-    Addr, BankMsg, Binary, Coin, CustomMsg, Deps, DepsMut, Env, MessageInfo, Response, Storage,
+    Addr, BankMsg, Binary, Coin, CustomMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    Storage,
 };
 
 use cw721::{ContractInfoResponse, Cw721Execute, Cw721ReceiveMsg, Expiration};
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg};
-use crate::state::{Approval, Cw721Contract, TokenInfo};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg};
+use crate::state::{Approval, Cw721Contract, TokenInfo, TransferMode};
+
+/// The maximum number of tokens in a single batch message
+pub const MAX_BATCH_SIZE: usize = 100;
 
 impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
 where
@@ -81,6 +85,21 @@ where
                 msg,
             } => self.send_nft(deps, env, info, contract, token_id, msg),
             ExecuteMsg::Burn { token_id } => self.burn(deps, env, info, token_id),
+            ExecuteMsg::BatchMint { mints } => self.batch_mint(deps, info, mints),
+            ExecuteMsg::BatchTransfer {
+                recipient,
+                token_ids,
+            } => self.batch_transfer(deps, env, info, recipient, token_ids),
+            ExecuteMsg::BatchBurn { token_ids } => self.batch_burn(deps, env, info, token_ids),
+            ExecuteMsg::UpdateNftInfo {
+                token_id,
+                token_uri,
+                extension,
+            } => self.update_nft_info(deps, info, token_id, token_uri, extension),
+            ExecuteMsg::FreezeMetadata {} => self.freeze_metadata(deps.storage, &info.sender),
+            ExecuteMsg::SetTransferMode { mode } => {
+                self.set_transfer_mode(deps.storage, &info.sender, mode)
+            }
             ExecuteMsg::UpdateOwnership(action) => Self::update_ownership(deps, env, info, action),
             ExecuteMsg::Extension { msg: _ } => Ok(Response::default()),
             ExecuteMsg::SetWithdrawAddress { address } => {
@@ -89,7 +108,15 @@ where
             ExecuteMsg::RemoveWithdrawAddress {} => {
                 self.remove_withdraw_address(deps.storage, &info.sender)
             }
-            ExecuteMsg::WithdrawFunds { amount } => self.withdraw_funds(deps.storage, &amount),
+            ExecuteMsg::WithdrawFunds { amount } => {
+                self.withdraw_funds(deps, &env, &info.sender, &amount)
+            }
+            ExecuteMsg::WithdrawAll { denom } => {
+                self.withdraw_all(deps, &env, &info.sender, &denom)
+            }
+            ExecuteMsg::SetWithdrawRestricted { restricted } => {
+                self.set_withdraw_restricted(deps.storage, &info.sender, restricted)
+            }
         }
     }
 }
@@ -112,21 +139,7 @@ where
         extension: T,
     ) -> Result<Response<C>, ContractError> {
         cw_ownable::assert_owner(deps.storage, &info.sender)?;
-
-        // create the token
-        let token = TokenInfo {
-            owner: deps.api.addr_validate(&owner)?,
-            approvals: vec![],
-            token_uri,
-            extension,
-        };
-        self.tokens
-            .update(deps.storage, &token_id, |old| match old {
-                Some(_) => Err(ContractError::Claimed {}),
-                None => Ok(token),
-            })?;
-
-        self.increment_tokens(deps.storage)?;
+        self._mint(deps, &token_id, &owner, token_uri, extension)?;
 
         Ok(Response::new()
             .add_attribute("action", "mint")
@@ -135,6 +148,123 @@ where
             .add_attribute("token_id", token_id))
     }
 
+    pub fn batch_mint(
+        &self,
+        mut deps: DepsMut,
+        info: MessageInfo,
+        mints: Vec<MintMsg<T>>,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        assert_batch_size(mints.len())?;
+
+        let mut token_ids = vec![];
+        for mint in mints {
+            self._mint(
+                deps.branch(),
+                &mint.token_id,
+                &mint.owner,
+                mint.token_uri,
+                mint.extension,
+            )?;
+            token_ids.push(mint.token_id);
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "batch_mint")
+            .add_attribute("minter", info.sender)
+            .add_attribute("token_ids", token_ids.join(",")))
+    }
+
+    pub fn batch_transfer(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        recipient: String,
+        token_ids: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        assert_batch_size(token_ids.len())?;
+        for token_id in token_ids.iter() {
+            self._transfer_nft(deps.branch(), &env, &info, &recipient, token_id)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "batch_transfer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("recipient", recipient)
+            .add_attribute("token_ids", token_ids.join(",")))
+    }
+
+    pub fn batch_burn(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_ids: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        assert_batch_size(token_ids.len())?;
+        for token_id in token_ids.iter() {
+            self._burn(deps.branch(), &env, &info, token_id)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "batch_burn")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_ids", token_ids.join(",")))
+    }
+
+    pub fn update_nft_info(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+        token_uri: Option<String>,
+        extension: T,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        if self.is_metadata_frozen(deps.storage)? {
+            return Err(ContractError::MetadataFrozen {});
+        }
+
+        self.tokens
+            .update(deps.storage, &token_id, |token| match token {
+                Some(mut token) => {
+                    token.token_uri = token_uri;
+                    token.extension = extension;
+                    Ok(token)
+                }
+                None => Err(StdError::not_found("cw721_base::state::TokenInfo<T>")),
+            })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_nft_info")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn freeze_metadata(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(storage, sender)?;
+        self.metadata_frozen.save(storage, &true)?;
+        Ok(Response::new().add_attribute("action", "freeze_metadata"))
+    }
+
+    pub fn set_transfer_mode(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        mode: TransferMode,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(storage, sender)?;
+        self.transfer_mode.save(storage, &mode)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_transfer_mode")
+            .add_attribute("mode", format!("{mode:?}")))
+    }
+
     pub fn update_ownership(
         deps: DepsMut,
         env: Env,
@@ -177,26 +307,82 @@ where
         }
     }
 
-    pub fn withdraw_funds(
+    pub fn set_withdraw_restricted(
         &self,
         storage: &mut dyn Storage,
+        sender: &Addr,
+        restricted: bool,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(storage, sender)?;
+        self.withdraw_restricted.save(storage, &restricted)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_withdraw_restricted")
+            .add_attribute("restricted", restricted.to_string()))
+    }
+
+    /// Checks that the sender can withdraw funds. If withdrawals are restricted, only the owner and
+    /// the withdraw address can.
+    pub fn assert_can_withdraw(
+        &self,
+        storage: &dyn Storage,
+        sender: &Addr,
+    ) -> Result<(), ContractError> {
+        if !self
+            .withdraw_restricted
+            .may_load(storage)?
+            .unwrap_or_default()
+        {
+            return Ok(());
+        }
+        if cw_ownable::get_ownership(storage)?.owner.as_ref() == Some(sender)
+            || self.withdraw_address.may_load(storage)?.as_deref() == Some(sender.as_str())
+        {
+            return Ok(());
+        }
+        Err(ContractError::WithdrawUnauthorized {})
+    }
+
+    pub fn withdraw_funds(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        sender: &Addr,
         amount: &Coin,
     ) -> Result<Response<C>, ContractError> {
-        let address = self.withdraw_address.may_load(storage)?;
-        match address {
-            Some(address) => {
-                let msg = BankMsg::Send {
-                    to_address: address,
-                    amount: vec![amount.clone()],
-                };
-                Ok(Response::new()
-                    .add_message(msg)
-                    .add_attribute("action", "withdraw_funds")
-                    .add_attribute("amount", amount.amount.to_string())
-                    .add_attribute("denom", amount.denom.to_string()))
-            }
-            None => Err(ContractError::NoWithdrawAddress {}),
+        self.assert_can_withdraw(deps.storage, sender)?;
+        let address = self
+            .withdraw_address
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoWithdrawAddress {})?;
+
+        let available = self.withdrawable_funds(deps.as_ref(), env, &amount.denom)?;
+        if amount.amount > available.amount {
+            return Err(ContractError::InsufficientFunds { available });
         }
+
+        let msg = BankMsg::Send {
+            to_address: address,
+            amount: vec![amount.clone()],
+        };
+        Ok(Response::new()
+            .add_message(msg)
+            .add_attribute("action", "withdraw_funds")
+            .add_attribute("amount", amount.amount.to_string())
+            .add_attribute("denom", amount.denom.to_string()))
+    }
+
+    pub fn withdraw_all(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        sender: &Addr,
+        denom: &str,
+    ) -> Result<Response<C>, ContractError> {
+        let available = self.withdrawable_funds(deps.as_ref(), env, denom)?;
+        if available.amount.is_zero() {
+            return Err(ContractError::InsufficientFunds { available });
+        }
+        self.withdraw_funds(deps, env, sender, &available)
     }
 }
 
@@ -296,6 +482,8 @@ where
         operator: String,
         expires: Option<Expiration>,
     ) -> Result<Response<C>, ContractError> {
+        self.assert_can_be_approved(deps.storage)?;
+
         // reject expired data as invalid
         let expires = expires.unwrap_or_default();
         if expires.is_expired(&env.block) {
@@ -337,11 +525,7 @@ where
         info: MessageInfo,
         token_id: String,
     ) -> Result<Response<C>, ContractError> {
-        let token = self.tokens.load(deps.storage, &token_id)?;
-        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-
-        self.tokens.remove(deps.storage, &token_id)?;
-        self.decrement_tokens(deps.storage)?;
+        self._burn(deps, &env, &info, &token_id)?;
 
         Ok(Response::new()
             .add_attribute("action", "burn")
@@ -358,6 +542,46 @@ where
     E: CustomMsg,
     Q: CustomMsg,
 {
+    pub fn _mint(
+        &self,
+        deps: DepsMut,
+        token_id: &str,
+        owner: &str,
+        token_uri: Option<String>,
+        extension: T,
+    ) -> Result<TokenInfo<T>, ContractError> {
+        // create the token
+        let token = TokenInfo {
+            owner: deps.api.addr_validate(owner)?,
+            approvals: vec![],
+            token_uri,
+            extension,
+        };
+        self.tokens
+            .update(deps.storage, token_id, |old| match old {
+                Some(_) => Err(ContractError::Claimed {}),
+                None => Ok(token.clone()),
+            })?;
+
+        self.increment_tokens(deps.storage)?;
+        Ok(token)
+    }
+
+    pub fn _burn(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        info: &MessageInfo,
+        token_id: &str,
+    ) -> Result<(), ContractError> {
+        let token = self.tokens.load(deps.storage, token_id)?;
+        self.check_can_send(deps.as_ref(), env, info, &token)?;
+
+        self.tokens.remove(deps.storage, token_id)?;
+        self.decrement_tokens(deps.storage)?;
+        Ok(())
+    }
+
     pub fn _transfer_nft(
         &self,
         deps: DepsMut,
@@ -368,7 +592,12 @@ where
     ) -> Result<TokenInfo<T>, ContractError> {
         let mut token = self.tokens.load(deps.storage, token_id)?;
         // ensure we have permissions
-        self.check_can_send(deps.as_ref(), env, info, &token)?;
+        match self.transfer_mode(deps.storage)? {
+            TransferMode::Soulbound => return Err(ContractError::NonTransferable {}),
+            TransferMode::MinterOnly => cw_ownable::assert_owner(deps.storage, &info.sender)
+                .map_err(|_| ContractError::NonTransferable {})?,
+            _ => self.check_can_send(deps.as_ref(), env, info, &token)?,
+        }
         // set owner and remove existing approvals
         token.owner = deps.api.addr_validate(recipient)?;
         token.approvals = vec![];
@@ -398,6 +627,7 @@ where
 
         // only difference between approve and revoke
         if add {
+            self.assert_can_be_approved(deps.storage)?;
             // reject expired data as invalid
             let expires = expires.unwrap_or_default();
             if expires.is_expired(&env.block) {
@@ -415,6 +645,16 @@ where
         Ok(token)
     }
 
+    /// tokens that only the minter can transfer, or no one at all, can't be approved
+    fn assert_can_be_approved(&self, storage: &dyn Storage) -> Result<(), ContractError> {
+        match self.transfer_mode(storage)? {
+            TransferMode::Soulbound | TransferMode::MinterOnly => {
+                Err(ContractError::NonTransferable {})
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// returns true iff the sender can execute approve or reject on the contract
     pub fn check_can_approve(
         &self,
@@ -481,3 +721,12 @@ where
         }
     }
 }
+
+fn assert_batch_size(size: usize) -> Result<(), ContractError> {
+    if size == 0 || size > MAX_BATCH_SIZE {
+        return Err(ContractError::InvalidBatchSize {
+            max: MAX_BATCH_SIZE,
+        });
+    }
+    Ok(())
+}
@@ -16,7 +16,7 @@ impl<Chain> Uploadable for Cw721<Chain> {
     /// Return the path to the wasm file corresponding to the contract
     fn wasm(_chain: &ChainInfoOwned) -> WasmPath {
         artifacts_dir_from_workspace!()
-            .find_wasm_path("cw721_non_transferable")
+            .find_wasm_path("cw721_base")
             .unwrap()
     }
     /// Returns a CosmWasm contract wrapper
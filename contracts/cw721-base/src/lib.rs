@@ -14,8 +14,9 @@ mod contract_tests;
 mod multi_tests;
 
 pub use crate::error::ContractError;
-pub use crate::msg::{ExecuteMsg, InstantiateMsg, MinterResponse, QueryMsg};
-pub use crate::state::Cw721Contract;
+pub use crate::execute::MAX_BATCH_SIZE;
+pub use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg, MinterResponse, QueryMsg};
+pub use crate::state::{Cw721Contract, TransferMode};
 
 // These types are re-exported so that contracts interacting with this
 // one don't need a direct dependency on cw_ownable to use the API.
@@ -4,6 +4,8 @@ use cw721::Expiration;
 use cw_orch::{ExecuteFns, QueryFns};
 use cw_ownable::{cw_ownable_execute, cw_ownable_query};
 
+use crate::state::TransferMode;
+
 #[cw_serde]
 pub struct InstantiateMsg {
     /// Name of the NFT contract
@@ -70,6 +72,28 @@ pub enum ExecuteMsg<T, E> {
     /// Burn an NFT the sender has access to
     Burn { token_id: String },
 
+    /// Mint multiple NFTs at once, can only be called by the contract minter
+    BatchMint { mints: Vec<MintMsg<T>> },
+    /// Transfer multiple NFTs the sender has access to to the same recipient
+    BatchTransfer {
+        recipient: String,
+        token_ids: Vec<String>,
+    },
+    /// Burn multiple NFTs the sender has access to
+    BatchBurn { token_ids: Vec<String> },
+
+    /// Replace the token_uri and extension of an NFT, can only be called by the contract minter
+    /// as long as the metadata is not frozen
+    UpdateNftInfo {
+        token_id: String,
+        token_uri: Option<String>,
+        extension: T,
+    },
+    /// Lock the metadata of all NFTs forever. Only owner can call this.
+    FreezeMetadata {},
+    /// Sets who can transfer the NFTs. Only owner can call this.
+    SetTransferMode { mode: TransferMode },
+
     /// Extension msg
     Extension { msg: E },
 
@@ -78,8 +102,13 @@ pub enum ExecuteMsg<T, E> {
     /// Removes the withdraw address, so fees are sent to the contract. Only owner can call this.
     RemoveWithdrawAddress {},
     /// Withdraw from the contract to the given address. Anyone can call this,
-    /// which is okay since withdraw address has been set by owner.
+    /// which is okay since withdraw address has been set by owner, unless withdrawals are restricted.
+    /// Reserved funds can't be withdrawn.
     WithdrawFunds { amount: Coin },
+    /// Withdraw the whole balance of a denom, minus the reserved funds
+    WithdrawAll { denom: String },
+    /// Restricts withdrawals to the owner and the withdraw address. Only owner can call this.
+    SetWithdrawRestricted { restricted: bool },
 }
 
 #[cw_ownable_query]
@@ -171,6 +200,35 @@ pub enum QueryMsg<Q: schemars::JsonSchema> {
 
     #[returns(Option<String>)]
     GetWithdrawAddress {},
+
+    /// Whether the metadata of the NFTs is frozen
+    #[returns(bool)]
+    MetadataFrozen {},
+
+    /// Who can transfer the NFTs
+    #[returns(TransferMode)]
+    GetTransferMode {},
+
+    /// Whether withdrawals are restricted to the owner and the withdraw address
+    #[returns(bool)]
+    WithdrawRestricted {},
+
+    /// The balance of a denom that can be withdrawn, excluding the reserved funds
+    #[returns(Coin)]
+    WithdrawableFunds { denom: String },
+}
+
+/// A single mint of a BatchMint
+#[cw_serde]
+pub struct MintMsg<T> {
+    /// Unique ID of the NFT
+    pub token_id: String,
+    /// The owner of the newly minted NFT
+    pub owner: String,
+    /// Universal resource identifier for this NFT
+    pub token_uri: Option<String>,
+    /// Any custom extension used by this contract
+    pub extension: T,
 }
 
 /// Shows who can mint these tokens
@@ -2,7 +2,7 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, BlockInfo, CustomMsg, Deps, Env, Order, StdError, StdResult,
+    to_json_binary, Addr, Binary, BlockInfo, Coin, CustomMsg, Deps, Env, Order, StdError, StdResult,
 };
 
 use cw721::{
@@ -329,6 +329,17 @@ where
             QueryMsg::GetWithdrawAddress {} => {
                 to_json_binary(&self.withdraw_address.may_load(deps.storage)?)
             }
+            QueryMsg::GetTransferMode {} => to_json_binary(&self.transfer_mode(deps.storage)?),
+            QueryMsg::MetadataFrozen {} => to_json_binary(&self.is_metadata_frozen(deps.storage)?),
+            QueryMsg::WithdrawRestricted {} => to_json_binary(
+                &self
+                    .withdraw_restricted
+                    .may_load(deps.storage)?
+                    .unwrap_or_default(),
+            ),
+            QueryMsg::WithdrawableFunds { denom } => {
+                to_json_binary(&self.withdrawable_funds(deps, &env, &denom)?)
+            }
         }
     }
 
@@ -340,6 +351,16 @@ where
         Ok(MinterResponse { minter })
     }
 
+    /// The balance of the contract in the given denom, minus the reserved funds
+    pub fn withdrawable_funds(&self, deps: Deps, env: &Env, denom: &str) -> StdResult<Coin> {
+        let balance = deps.querier.query_balance(&env.contract.address, denom)?;
+        let reserved = self.reserved(deps.storage, denom)?;
+        Ok(Coin {
+            denom: balance.denom,
+            amount: balance.amount.saturating_sub(reserved),
+        })
+    }
+
     pub fn ownership(deps: Deps) -> StdResult<cw_ownable::Ownership<Addr>> {
         cw_ownable::get_ownership(deps.storage)
     }
@@ -1,9 +1,10 @@
+use cosmwasm_schema::cw_serde;
 use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
-use cosmwasm_std::{Addr, BlockInfo, CustomMsg, StdResult, Storage};
+use cosmwasm_std::{Addr, BlockInfo, CustomMsg, StdResult, Storage, Uint128};
 
 use cw721::{ContractInfoResponse, Cw721, Expiration};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
@@ -20,6 +21,13 @@ where
     pub operators: Map<'a, (&'a Addr, &'a Addr), Expiration>,
     pub tokens: IndexedMap<'a, &'a str, TokenInfo<T>, TokenIndexes<'a, T>>,
     pub withdraw_address: Item<'a, String>,
+    /// If set, only the owner or the withdraw address can withdraw funds
+    pub withdraw_restricted: Item<'a, bool>,
+    /// Funds per denom that belong to someone else, like refundable deposits, and can't be withdrawn
+    pub reserved_funds: Map<'a, &'a str, Uint128>,
+    /// If set, the metadata of the tokens is locked forever
+    pub metadata_frozen: Item<'a, bool>,
+    pub transfer_mode: Item<'a, TransferMode>,
 
     pub(crate) _custom_response: PhantomData<C>,
     pub(crate) _custom_query: PhantomData<Q>,
@@ -50,6 +58,10 @@ where
             "tokens",
             "tokens__owner",
             "withdraw_address",
+            "withdraw_restricted",
+            "reserved_funds",
+            "metadata_frozen",
+            "transfer_mode",
         )
     }
 }
@@ -67,6 +79,10 @@ where
         tokens_key: &'a str,
         tokens_owner_key: &'a str,
         withdraw_address_key: &'a str,
+        withdraw_restricted_key: &'a str,
+        reserved_funds_key: &'a str,
+        metadata_frozen_key: &'a str,
+        transfer_mode_key: &'a str,
     ) -> Self {
         let indexes = TokenIndexes {
             owner: MultiIndex::new(token_owner_idx, tokens_key, tokens_owner_key),
@@ -77,6 +93,10 @@ where
             operators: Map::new(operator_key),
             tokens: IndexedMap::new(tokens_key, indexes),
             withdraw_address: Item::new(withdraw_address_key),
+            withdraw_restricted: Item::new(withdraw_restricted_key),
+            reserved_funds: Map::new(reserved_funds_key),
+            metadata_frozen: Item::new(metadata_frozen_key),
+            transfer_mode: Item::new(transfer_mode_key),
             _custom_response: PhantomData,
             _custom_execute: PhantomData,
             _custom_query: PhantomData,
@@ -93,6 +113,49 @@ where
         Ok(val)
     }
 
+    pub fn transfer_mode(&self, storage: &dyn Storage) -> StdResult<TransferMode> {
+        Ok(self.transfer_mode.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn is_metadata_frozen(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self.metadata_frozen.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn reserved(&self, storage: &dyn Storage, denom: &str) -> StdResult<Uint128> {
+        Ok(self
+            .reserved_funds
+            .may_load(storage, denom)?
+            .unwrap_or_default())
+    }
+
+    /// Reserves funds held by the contract, so they are excluded from withdrawals
+    pub fn reserve_funds(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<Uint128> {
+        let val = self.reserved(storage, denom)?.checked_add(amount)?;
+        self.reserved_funds.save(storage, denom, &val)?;
+        Ok(val)
+    }
+
+    /// Releases reserved funds, e.g. once a deposit has been refunded
+    pub fn release_funds(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<Uint128> {
+        let val = self.reserved(storage, denom)?.checked_sub(amount)?;
+        if val.is_zero() {
+            self.reserved_funds.remove(storage, denom);
+        } else {
+            self.reserved_funds.save(storage, denom, &val)?;
+        }
+        Ok(val)
+    }
+
     pub fn decrement_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
         let val = self.token_count(storage)? - 1;
         self.token_count.save(storage, &val)?;
@@ -100,6 +163,25 @@ where
     }
 }
 
+/// Who can transfer the tokens
+#[cw_serde]
+#[derive(Default)]
+pub enum TransferMode {
+    /// the owner of a token and the approved addresses can transfer it
+    #[default]
+    Transferable,
+    /// tokens can never be transferred or approved, only burned
+    Soulbound,
+    /// only the minter can transfer tokens, e.g. to recover them for their owners
+    MinterOnly,
+    /// tokens can only be transferred while they are alive. Contracts extending this one have to
+    /// check this, for the base contract it is the same as Transferable
+    WhileAlive,
+    /// tokens can only be transferred while they are unhatched. Contracts extending this one have
+    /// to check this, for the base contract it is the same as Transferable
+    WhileUnhatched,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TokenInfo<T> {
     /// The owner of the newly minted NFT
@@ -25,6 +25,7 @@ fn main() -> anyhow::Result<()> {
                     max_unfed_days: None,
                     feeding_cost_multiplier: Some(0),
                     graveyard: None,
+                    ..Default::default()
                 }
             },
         },
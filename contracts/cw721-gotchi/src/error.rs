@@ -1,5 +1,6 @@
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Coin, Timestamp};
 use cw721_base::error::ContractError as Cw721ContractError;
+use cw_ownable::OwnershipError;
 use thiserror::Error;
 
 pub type CResult<T> = std::result::Result<T, ContractError>;
@@ -35,6 +36,180 @@ pub enum ContractError {
 
     #[error("Not all items are dead")]
     NotAllDead {},
+
+    #[error("Not allowed to feed this magotchi")]
+    NotAllowedToFeed {},
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("The magotchi is not dead")]
+    MagotchiNotDead {},
+
+    #[error("Reviving magotchis is not enabled")]
+    RevivalDisabled {},
+
+    #[error("The magotchi has been revived the maximum of {max} times")]
+    MaxRevivalsReached { max: u32 },
+
+    #[error("Invalid payment {payed:?}, expected {expected:?}")]
+    InvalidPayment { payed: Coin, expected: Coin },
+
+    #[error("Magotchi {token_id} owes no hunger tax, so the transfer doesn't accept funds")]
+    NoHungerTaxDue { token_id: String },
+
+    #[error("Cannot pay with denom {denom}")]
+    InvalidDenom { denom: String },
+
+    #[error("Vacations are not enabled")]
+    VacationDisabled {},
+
+    #[error("Care actions are not enabled")]
+    CareDisabled {},
+
+    #[error("There is no item {item}")]
+    UnknownItem { item: String },
+
+    #[error("Not enough {item} in the inventory")]
+    InsufficientItems { item: String },
+
+    #[error("The amount must be greater than zero")]
+    InvalidAmount {},
+
+    #[error("The magotchi is already on vacation")]
+    AlreadyOnVacation {},
+
+    #[error("A vacation must last between 1 and {max} days")]
+    InvalidVacationDays { max: u32 },
+
+    #[error("Not enough vacation days left this year, {remaining} remaining")]
+    VacationLimitReached { remaining: u32 },
+
+    #[error(transparent)]
+    Ownership(#[from] OwnershipError),
+
+    #[error("The contract is paused")]
+    ContractPaused {},
+
+    #[error("The contract is not paused")]
+    NotPaused {},
+
+    #[error("Breeding is not enabled")]
+    BreedingDisabled {},
+
+    #[error("A magotchi cannot breed with itself")]
+    CannotBreedWithItself {},
+
+    #[error("The magotchi is too young to breed")]
+    TooYoungToBreed {},
+
+    #[error("The magotchi needs to rest before it can breed again")]
+    BreedingCooldown {},
+
+    #[error("The magotchi can't do this again until {ready_at}")]
+    CareCooldown { ready_at: Timestamp },
+
+    #[error("Minting is not enabled")]
+    MintDisabled {},
+
+    #[error("Minting has not started")]
+    MintNotStarted {},
+
+    #[error("Minting has ended")]
+    MintEnded {},
+
+    #[error("All magotchis have been minted")]
+    SoldOut {},
+
+    #[error("Cannot mint more than {limit} magotchis")]
+    MintLimitReached { limit: u32 },
+
+    #[error("Invalid merkle proof")]
+    InvalidMerkleProof {},
+
+    #[error("Magotchi {token_id} is dead and can't be transferred until it is revived or reaped")]
+    DeadNotTransferable { token_id: String },
+
+    #[error(
+        "Magotchi {token_id} is hungry, its feeding cost must be paid before it can be transferred"
+    )]
+    HungerTaxDue { token_id: String },
+
+    #[error("Invalid commitment, expected a hex encoded sha256 hash")]
+    InvalidCommitment {},
+
+    #[error("The revealed metadata doesn't match any commitment")]
+    InvalidReveal {},
+
+    #[error("Magotchi {token_id} is already revealed")]
+    AlreadyRevealed { token_id: String },
+
+    #[error("The marketplace is not enabled")]
+    MarketplaceDisabled {},
+
+    #[error("Magotchi {token_id} is not listed for sale")]
+    NotListed { token_id: String },
+
+    #[error("The listing of magotchi {token_id} has expired")]
+    ListingExpired { token_id: String },
+
+    #[error("The price must be greater than zero")]
+    InvalidPrice {},
+
+    #[error("Magotchi {token_id} is not in auction")]
+    NotInAuction { token_id: String },
+
+    #[error("The auction of magotchi {token_id} has ended")]
+    AuctionEnded { token_id: String },
+
+    #[error("The auction of magotchi {token_id} has not ended yet")]
+    AuctionNotEnded { token_id: String },
+
+    #[error("The auction of magotchi {token_id} is cancelled, because the magotchi died")]
+    AuctionCancelled { token_id: String },
+
+    #[error("The auction of magotchi {token_id} already has bids")]
+    AuctionHasBids { token_id: String },
+
+    #[error("The bid must be at least {min_bid}")]
+    BidTooLow { min_bid: Coin },
+
+    #[error("Magotchi {token_id} is rented out")]
+    RentalActive { token_id: String },
+
+    #[error("Magotchi {token_id} is not rented out")]
+    NotRented { token_id: String },
+
+    #[error("Rentals must expire")]
+    RentalNeverExpires {},
+
+    #[error("Commit-reveal randomness is not enabled")]
+    RandomnessDisabled {},
+
+    #[error("The commit phase of the randomness round is over")]
+    CommitPhaseOver {},
+
+    #[error("The sender already committed to this randomness round")]
+    AlreadyCommitted {},
+
+    #[error("The randomness round already has the maximum of {max} players")]
+    RoundFull { max: usize },
+
+    #[error("The reveal phase of the randomness round has not started")]
+    RevealPhaseNotStarted {},
+
+    #[error("The reveal phase of the randomness round is over")]
+    RevealPhaseOver {},
+
+    #[error("The sender has no unrevealed commitment in this randomness round")]
+    NotCommitted {},
+
+    #[error("The secret doesn't match the commitment")]
+    InvalidSecret {},
+
+    #[error("The randomness round of magotchi {token_id} is not complete")]
+    RandomnessNotReady { token_id: String },
+
     #[error("Invalid config. Minimum days unfed must be greater than 1. Feeding cost must contain a valid Coin. Graveyard must be set.")]
     InvalidConfig {},
 }
@@ -1,11 +1,31 @@
-use cosmwasm_std::{BlockInfo, Coin, Deps, DepsMut, Empty, Env, Order, Response};
+use cosmwasm_std::{
+    Addr, BankMsg, BlockInfo, Coin, Deps, DepsMut, Empty, Env, MessageInfo, Order, OverflowError,
+    Response, StdError, StdResult, Storage, Timestamp, Uint128,
+};
+use cw721::Expiration;
+use cw721_base::{state::Approval, TransferMode};
+use cw_storage_plus::Item;
 
 use crate::{
     error::{CResult, ContractError},
-    state::{Gotchi, PartialConfig, CONFIG, LIVE_STATES},
-    Cw721MetadataContract,
+    genetics::{generate_genome, genome_from_dna, inherit_genome, merge_traits},
+    merkle::{decode_hash, verify_proof},
+    randomness::Round,
+    state::{
+        active_user, graves, listings, load_gotchi, Auction, Bid, CareAction, Caretaker, Config,
+        FeedingPolicy, Gotchi, Grave, ItemEffect, Listing, MarketplaceConfig, PartialConfig,
+        PausePeriod, Recipient, Rental, RevealBatch, RoyaltyConfig, ACCRUED, AUCTIONS, CARETAKERS,
+        CONFIG, FEEDING_ALLOWLIST, INVENTORIES, LIVE_STATES, MAX_TOP_CARETAKERS, NEXT_EGG_ID,
+        NEXT_TOKEN_ID, PAUSE, PRESALE_MINTED, PRESALE_SUPPLY, PUBLIC_MINTED, PUBLIC_SUPPLY,
+        RANDOMNESS_ROUNDS, RENTALS, REVEAL_COMMITMENTS, REVENUE, TOKEN_ROYALTIES, TOP_CARETAKERS,
+        UNREVEALED,
+    },
+    Cw721MetadataContract, Extension, Metadata, Trait,
 };
 
+/// The maximum number of long-term caretakers that share in the rewards of a single feeding
+const MAX_REWARDED_CARETAKERS: usize = 5;
+
 pub fn parse_funds(funds: &Vec<Coin>) -> Result<Coin, ContractError> {
     if funds.is_empty() {
         return Err(ContractError::FeedingIsNotFree {});
@@ -23,15 +43,19 @@ pub fn parse_funds(funds: &Vec<Coin>) -> Result<Coin, ContractError> {
 pub fn execute_feed(
     deps: &mut DepsMut,
     env: &Env,
+    info: &MessageInfo,
     token_id: &str,
-    funds: &Vec<Coin>,
 ) -> Result<Response, ContractError> {
-    let paying_coin = parse_funds(funds)?;
+    assert_not_paused(deps.storage)?;
+    let paying_coin = parse_funds(&info.funds)?;
 
     let config = CONFIG.load(deps.as_ref().storage)?;
-    let state = LIVE_STATES.load(deps.storage, token_id.to_string())?;
+    assert_can_feed(deps.as_ref(), env, info, &config.feeding_policy, token_id)?;
+    let mut state = load_gotchi(deps.storage, token_id)?;
 
-    let total_feeding_cost = config.get_total_feeding_cost(&state, &env.block, &funds[0].denom)?;
+    // the cost is the one the queries show, from before the magotchi is rolled for sickness
+    let total_feeding_cost =
+        config.get_total_feeding_cost(&state, &env.block, &paying_coin.denom)?;
 
     if paying_coin != total_feeding_cost {
         return Err(ContractError::InvalidFeedingCost {
@@ -40,12 +64,237 @@ pub fn execute_feed(
         });
     }
 
-    LIVE_STATES.update(deps.storage, token_id.to_string(), |old| match old {
-        Some(mut old) => Ok(old.feed(&env.block, config.max_unfed_days.into())?),
-        None => Err(ContractError::MagotchiUnhatched {}),
-    })?;
+    // rewards are calculated before the current feeding is recorded, so feeders never reward themselves
+    let rewards =
+        get_caretaker_rewards(deps.as_ref(), &config, token_id, &info.sender, &paying_coin)?;
+    let contribution = config.get_feeding_cost(&state, &env.block);
+
+    roll_sickness(&env.block, &config, token_id, &mut state);
+    state.feed(&env.block, config.max_unfed_days_for(&state))?;
+    if let Some(care) = &config.care {
+        state.neglect(&env.block, care);
+    }
+    LIVE_STATES.save(deps.storage, token_id.to_string(), &state)?;
+
+    let rewarded: Uint128 = rewards
+        .iter()
+        .filter_map(|msg| match msg {
+            BankMsg::Send { amount, .. } => Some(amount),
+            _ => None,
+        })
+        .flatten()
+        .map(|coin| coin.amount)
+        .sum();
+    record_revenue(
+        deps.storage,
+        &config,
+        &Coin {
+            denom: paying_coin.denom.clone(),
+            amount: paying_coin.amount - rewarded,
+        },
+    )?;
+
+    CARETAKERS.update(
+        deps.storage,
+        (token_id, &info.sender),
+        |caretaker| -> StdResult<_> {
+            let mut caretaker = caretaker.unwrap_or_default();
+            caretaker.feedings += 1;
+            caretaker.contribution += contribution;
+            Ok(caretaker)
+        },
+    )?;
+    update_top_caretakers(deps.storage, token_id, &info.sender)?;
+
+    Ok(Response::default()
+        .add_messages(rewards)
+        .add_attributes(vec![
+            ("action", "feed"),
+            ("token_id", token_id),
+            ("feeder", info.sender.as_str()),
+        ]))
+}
+
+/// Checks the feeding policy against the cw721 owner, approvals and operators and the borrower of
+/// the magotchi
+pub fn assert_can_feed(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    policy: &FeedingPolicy,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    if *policy == FeedingPolicy::Open {
+        return Ok(());
+    }
+
+    let contract = Cw721MetadataContract::default();
+    let token = contract.tokens.load(deps.storage, token_id)?;
+    if contract.check_can_send(deps, env, info, &token).is_ok() {
+        return Ok(());
+    }
+    if active_user(deps.storage, &env.block, token_id)?.as_ref() == Some(&info.sender) {
+        return Ok(());
+    }
+
+    match policy {
+        FeedingPolicy::Allowlist
+            if FEEDING_ALLOWLIST.has(deps.storage, (token_id, &info.sender)) =>
+        {
+            Ok(())
+        }
+        _ => Err(ContractError::NotAllowedToFeed {}),
+    }
+}
+
+/// Loads the live state of a magotchi, rolling whether it got sick since it was last touched
+fn touch_gotchi(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    config: &Config,
+    token_id: &str,
+) -> StdResult<Gotchi> {
+    let mut state = load_gotchi(storage, token_id)?;
+    roll_sickness(block, config, token_id, &mut state);
+    Ok(state)
+}
+
+/// Rolls whether the magotchi got sick since it was last touched. The roll never kills the
+/// magotchi, so it can't make the action that touches it fail, which would undo the roll
+fn roll_sickness(block: &BlockInfo, config: &Config, token_id: &str, state: &mut Gotchi) {
+    if let Some(sickness) = &config.sickness {
+        state.roll_sickness(block, token_id, sickness, config.max_unfed_days_for(state));
+    }
+}
+
+pub fn execute_update_feeding_allowlist(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    feeder: &str,
+    // if allow == false, remove the feeder from the allowlist
+    allow: bool,
+) -> Result<Response, ContractError> {
+    let contract = Cw721MetadataContract::default();
+    let token = contract.tokens.load(deps.storage, token_id)?;
+    contract.check_can_approve(deps.as_ref(), env, info, &token)?;
+
+    let feeder_addr = deps.api.addr_validate(feeder)?;
+    if allow {
+        FEEDING_ALLOWLIST.save(deps.storage, (token_id, &feeder_addr), &Empty {})?;
+    } else {
+        FEEDING_ALLOWLIST.remove(deps.storage, (token_id, &feeder_addr));
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        (
+            "action",
+            if allow {
+                "allow_feeder"
+            } else {
+                "disallow_feeder"
+            },
+        ),
+        ("token_id", token_id),
+        ("feeder", feeder),
+    ]))
+}
+
+/// Clears the feeding allowlists of magotchis that changed owner, so the new owner doesn't inherit
+/// the feeders of the previous one
+pub fn clear_feeding_allowlists(storage: &mut dyn Storage, token_ids: &[String]) -> StdResult<()> {
+    for token_id in token_ids {
+        let feeders = FEEDING_ALLOWLIST
+            .prefix(token_id)
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<Addr>>>()?;
+        for feeder in feeders {
+            FEEDING_ALLOWLIST.remove(storage, (token_id, &feeder));
+        }
+    }
+    Ok(())
+}
+
+/// Ends the rentals of magotchis that changed owner, so the new owner isn't bound by them
+pub fn end_rentals(storage: &mut dyn Storage, token_ids: &[String]) {
+    for token_id in token_ids {
+        RENTALS.remove(storage, token_id);
+    }
+}
 
-    Ok(Response::default().add_attributes(vec![("action", "feed"), ("token_id", token_id)]))
+/// Splits the configured share of a feeding payment between the long-term caretakers of a magotchi,
+/// proportional to their contribution. The feeder itself is never rewarded.
+pub fn get_caretaker_rewards(
+    deps: Deps,
+    config: &Config,
+    token_id: &str,
+    feeder: &Addr,
+    payment: &Coin,
+) -> StdResult<Vec<BankMsg>> {
+    let rewards = match &config.caretaker_rewards {
+        Some(rewards) if rewards.share > 0 => rewards,
+        _ => return Ok(vec![]),
+    };
+
+    let mut caretakers: Vec<(Addr, Caretaker)> = vec![];
+    for address in TOP_CARETAKERS
+        .may_load(deps.storage, token_id)?
+        .unwrap_or_default()
+    {
+        if caretakers.len() == MAX_REWARDED_CARETAKERS {
+            break;
+        }
+        let caretaker = CARETAKERS.load(deps.storage, (token_id, &address))?;
+        if &address != feeder && caretaker.feedings >= rewards.min_feedings {
+            caretakers.push((address, caretaker));
+        }
+    }
+
+    let total_contribution: u64 = caretakers.iter().map(|(_, c)| c.contribution).sum();
+    if total_contribution == 0 {
+        return Ok(vec![]);
+    }
+
+    let reward_pool = payment.amount.multiply_ratio(rewards.share, 1000u64);
+    Ok(caretakers
+        .into_iter()
+        .filter_map(|(address, caretaker)| {
+            let amount = reward_pool.multiply_ratio(caretaker.contribution, total_contribution);
+            (!amount.is_zero()).then(|| BankMsg::Send {
+                to_address: address.into_string(),
+                amount: vec![Coin {
+                    denom: payment.denom.clone(),
+                    amount,
+                }],
+            })
+        })
+        .collect())
+}
+
+/// Moves the feeder to its place among the top caretakers of the magotchi, after its contribution
+/// grew. Caretakers that drop below the top are only ranked again once they feed
+fn update_top_caretakers(
+    storage: &mut dyn Storage,
+    token_id: &str,
+    feeder: &Addr,
+) -> StdResult<()> {
+    let mut top = TOP_CARETAKERS
+        .may_load(storage, token_id)?
+        .unwrap_or_default();
+    top.retain(|address| address != feeder);
+
+    let contribution = CARETAKERS.load(storage, (token_id, feeder))?.contribution;
+    let mut position = top.len();
+    for (index, address) in top.iter().enumerate() {
+        if CARETAKERS.load(storage, (token_id, address))?.contribution < contribution {
+            position = index;
+            break;
+        }
+    }
+    top.insert(position, feeder.clone());
+    top.truncate(MAX_TOP_CARETAKERS);
+    TOP_CARETAKERS.save(storage, token_id, &top)
 }
 
 pub fn execute_hatch(
@@ -53,10 +302,33 @@ pub fn execute_hatch(
     env: &Env,
     token_id: &str,
 ) -> Result<Response, ContractError> {
-    LIVE_STATES.update(deps.storage, token_id.to_string(), |old| match old {
-        Some(mut old) => Ok(old.hatch(&env.block)?),
-        None => Err(ContractError::not_found()),
-    })?;
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let mut state = load_gotchi(deps.storage, token_id)?;
+    state.hatch(&env.block)?;
+
+    if let Some(genetics) = &config.genetics {
+        let genome = match (state.genome(), &config.randomness) {
+            // bred eggs already carry the genes they inherited from their parents
+            (Some(genome), _) => genome.clone(),
+            (None, Some(_)) => genome_from_dna(
+                genetics,
+                take_seed(deps.storage, &config, &env.block, token_id)?,
+            ),
+            (None, None) => generate_genome(genetics, token_id, env),
+        };
+        // frozen metadata stays as it is, the genome is only kept with the live state
+        let contract = Cw721MetadataContract::default();
+        if !contract.is_metadata_frozen(deps.storage)? {
+            let mut token = contract.tokens.load(deps.storage, token_id)?;
+            let mut extension = token.extension.unwrap_or_default();
+            extension.attributes = Some(merge_traits(extension.attributes, &genome.traits));
+            token.extension = Some(extension);
+            contract.tokens.save(deps.storage, token_id, &token)?;
+        }
+        state.set_genome(genome);
+    }
+    LIVE_STATES.save(deps.storage, token_id.to_string(), &state)?;
 
     Ok(Response::default().add_attributes(vec![
         ("action", "hatch"),
@@ -65,479 +337,4750 @@ pub fn execute_hatch(
     ]))
 }
 
+/// Takes the seed of the complete randomness round of the magotchi, keeping the bonds of the
+/// players that never revealed their secret
+fn take_seed(
+    storage: &mut dyn Storage,
+    config: &Config,
+    block: &BlockInfo,
+    token_id: &str,
+) -> Result<[u8; 32], ContractError> {
+    let not_ready = || ContractError::RandomnessNotReady {
+        token_id: token_id.to_string(),
+    };
+    let round = RANDOMNESS_ROUNDS
+        .may_load(storage, token_id)?
+        .ok_or_else(not_ready)?;
+    let seed = round.seed(token_id, block).ok_or_else(not_ready)?;
+    forfeit_bonds(storage, config, &round)?;
+    RANDOMNESS_ROUNDS.remove(storage, token_id);
+    Ok(seed)
+}
+
+/// Releases the bonds of the players that never revealed their secret, and records them as
+/// revenue
+fn forfeit_bonds(storage: &mut dyn Storage, config: &Config, round: &Round) -> StdResult<()> {
+    let contract = Cw721MetadataContract::default();
+    for bond in round.forfeited() {
+        contract.release_funds(storage, &bond.denom, bond.amount)?;
+        record_revenue(storage, config, &bond)?;
+    }
+    Ok(())
+}
+
+/// Commits to a secret for the randomness round of an unhatched magotchi, starting a new round
+/// if there is none. Anyone can call this
+pub fn execute_commit_randomness(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    commitment: &str,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let randomness = config
+        .randomness
+        .as_ref()
+        .ok_or(ContractError::RandomnessDisabled {})?;
+    if load_gotchi(deps.storage, token_id)?.is_hatched() {
+        return Err(ContractError::MagotchiAlreadyHatched {});
+    }
+
+    let bond = assert_payment(&info.funds, &randomness.bond)?;
+    let mut round = match RANDOMNESS_ROUNDS.may_load(deps.storage, token_id)? {
+        Some(round) if !round.is_expired(&env.block) => round,
+        // nobody revealed in the last round, so it starts over
+        expired => {
+            if let Some(expired) = expired {
+                forfeit_bonds(deps.storage, &config, &expired)?;
+            }
+            Round::new(&env.block, randomness)
+        }
+    };
+    // bonds can't be withdrawn with the rest of the funds of the contract until they are settled
+    Cw721MetadataContract::default().reserve_funds(deps.storage, &bond.denom, bond.amount)?;
+    round.commit(&env.block, &info.sender, commitment, Some(bond))?;
+    RANDOMNESS_ROUNDS.save(deps.storage, token_id, &round)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "commit_randomness"),
+        ("token_id", token_id),
+        ("player", info.sender.as_str()),
+    ]))
+}
+
+/// Reveals the secret of the sender in the randomness round of the magotchi, refunding the bond
+pub fn execute_reveal_randomness(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    secret: &str,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let mut round = RANDOMNESS_ROUNDS
+        .may_load(deps.storage, token_id)?
+        .ok_or(ContractError::NotCommitted {})?;
+    let bond = round.reveal(&env.block, &info.sender, secret)?;
+    RANDOMNESS_ROUNDS.save(deps.storage, token_id, &round)?;
+    if let Some(bond) = &bond {
+        Cw721MetadataContract::default().release_funds(deps.storage, &bond.denom, bond.amount)?;
+    }
+
+    let refund = bond.map(|bond| BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: vec![bond],
+    });
+    Ok(Response::default()
+        .add_messages(refund)
+        .add_attributes(vec![
+            ("action", "reveal_randomness"),
+            ("token_id", token_id),
+            ("player", info.sender.as_str()),
+        ]))
+}
+
 pub fn execute_reap(
     deps: &mut DepsMut<Empty>,
     tokens: Option<Vec<String>>,
     env: &Env,
+    info: &MessageInfo,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
     let contract = Cw721MetadataContract::default();
     let config = CONFIG.load(deps.as_ref().storage)?;
     let tokens = match tokens {
         Some(tokens) => tokens,
-        None => get_all_dead(deps.as_ref(), &env.block),
+        None => get_all_dead(deps.as_ref(), &env.block)?,
     };
 
+    let mut reaped = 0;
     tokens.iter().try_for_each(|token_id| -> CResult<()> {
-        let state = LIVE_STATES.load(deps.storage, token_id.clone())?;
+        let state = load_gotchi(deps.storage, token_id)?;
 
         // if any are not dead, we cannot reap
         if !state.is_dead(&env.block) {
             return Err(ContractError::NotAllDead {});
         }
 
-        contract
-            .tokens
-            .update(deps.storage, token_id, |t| match t {
-                Some(mut token) => {
-                    token.owner = config.graveyard.clone();
-                    Ok(token)
-                }
-                None => Err(ContractError::not_found()),
-            })?;
+        // already reaped magotchis keep their grave, magotchis in auction are settled instead
+        if graves().has(deps.storage, token_id.clone()) || AUCTIONS.has(deps.storage, token_id) {
+            return Ok(());
+        }
+
+        let mut token = contract.tokens.load(deps.storage, token_id)?;
+        graves().save(
+            deps.storage,
+            token_id.clone(),
+            &Grave {
+                former_owner: token.owner,
+                died_at: state.death_time(),
+                reaped_at: env.block.time,
+            },
+        )?;
+
+        // the graveyard doesn't sell the magotchis of their former owners
+        token.owner = config.graveyard.clone();
+        token.approvals = vec![];
+        contract.tokens.save(deps.storage, token_id, &token)?;
+        listings().remove(deps.storage, token_id)?;
+        clear_feeding_allowlists(deps.storage, &[token_id.clone()])?;
+        end_rentals(deps.storage, &[token_id.clone()]);
+        reaped += 1;
         Ok(())
     })?;
-    Ok(Response::default().add_attributes(vec![
+
+    // the reaper rewards go to whoever digs the next graves
+    let rewards = if reaped > 0 {
+        take_accrued(deps.storage, &Recipient::Reapers {}.key(), None)?
+    } else {
+        vec![]
+    };
+    let mut response = Response::default();
+    if !rewards.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: rewards,
+        });
+    }
+
+    Ok(response.add_attributes(vec![
         ("action", "reap"),
         ("tokens", tokens.clone().join(",").as_str()),
     ]))
 }
 
-pub fn get_all_dead(deps: Deps, block: &BlockInfo) -> Vec<String> {
-    LIVE_STATES
-        .range(deps.storage, None, None, Order::Ascending)
-        .map(Result::unwrap)
-        .filter_map(|(token_id, state)| state.is_dead(block).then(|| token_id.to_string()))
-        .collect()
+/// Records revenue in the treasury ledger, splitting it between the recipients of the treasury
+/// config. Without a treasury config, the revenue is only withdrawable with the cw721-base
+/// WithdrawFunds
+pub fn record_revenue(storage: &mut dyn Storage, config: &Config, revenue: &Coin) -> StdResult<()> {
+    let Some(treasury) = &config.treasury else {
+        return Ok(());
+    };
+    if revenue.amount.is_zero() {
+        return Ok(());
+    }
+
+    REVENUE.update(storage, &revenue.denom, |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default() + revenue.amount)
+    })?;
+
+    // the last recipient gets the rounding remainder
+    let mut remaining = revenue.amount;
+    for (index, split) in treasury.splits.iter().enumerate() {
+        let amount = if index == treasury.splits.len() - 1 {
+            remaining
+        } else {
+            revenue.amount.multiply_ratio(split.share, 1000u128)
+        };
+        remaining -= amount;
+        ACCRUED.update(
+            storage,
+            (&split.recipient.key(), &revenue.denom),
+            |accrued| -> StdResult<_> { Ok(accrued.unwrap_or_default() + amount) },
+        )?;
+    }
+    Ok(())
 }
 
-pub fn execute_mint(deps: &mut DepsMut, token_id: String) -> Result<(), ContractError> {
-    LIVE_STATES
-        .save(deps.storage, token_id.to_string(), &Gotchi::new())
-        .map_err(Into::into)
+/// Removes and returns the accrued amounts of a recipient, only of the given denom if set
+fn take_accrued(
+    storage: &mut dyn Storage,
+    recipient: &str,
+    denom: Option<&str>,
+) -> StdResult<Vec<Coin>> {
+    let accrued = ACCRUED
+        .prefix(recipient)
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref().map_or(true, |(d, _)| {
+                denom.map_or(true, |denom| d.as_str() == denom)
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    accrued
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(denom, amount)| {
+            ACCRUED.remove(storage, (recipient, &denom));
+            Ok(Coin { denom, amount })
+        })
+        .collect()
 }
 
-pub fn execute_update_config(
+/// Pays out the accrued revenue in the given denom to the address recipients, including those
+/// that were removed from the treasury config since. The reaper rewards stay in the ledger until
+/// the next reap
+pub fn execute_withdraw_treasury(
     deps: &mut DepsMut,
-    partial_config: PartialConfig,
+    config: &Config,
+    denom: &str,
 ) -> Result<Response, ContractError> {
-    CONFIG.update(deps.storage, |mut config| -> Result<_, ContractError> {
-        if let Some(daily_feeding_cost) = partial_config.daily_feeding_cost {
-            config.daily_feeding_cost = daily_feeding_cost;
-        }
-        if let Some(max_unfed_days) = partial_config.max_unfed_days {
-            config.max_unfed_days = max_unfed_days;
-        }
-        if let Some(feeding_cost_multiplier) = partial_config.feeding_cost_multiplier {
-            config.feeding_cost_multiplier = feeding_cost_multiplier;
-        }
-        config.validate()?;
-
-        Ok(config)
-    })?;
+    if config.treasury.is_none() {
+        return Err(ContractError::InvalidConfig {});
+    }
 
-    Ok(Response::default())
-}
+    let reapers = Recipient::Reapers {}.key();
+    let recipients = ACCRUED
+        .keys(deps.storage, None, None, Order::Ascending)
+        .filter(|key| {
+            key.as_ref()
+                .map_or(true, |(recipient, d)| d == denom && recipient != &reapers)
+        })
+        .map(|key| key.map(|(recipient, _)| recipient))
+        .collect::<StdResult<Vec<String>>>()?;
 
-#[cfg(test)]
-mod tests {
-    use crate::state::Config;
+    let mut messages = vec![];
+    for recipient in recipients {
+        let amount = take_accrued(deps.storage, &recipient, Some(denom))?;
+        if !amount.is_empty() {
+            messages.push(BankMsg::Send {
+                to_address: recipient,
+                amount,
+            });
+        }
+    }
 
-    use super::*;
-    use cosmwasm_std::{
-        attr, coin, coins,
-        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier},
-        Addr, MemoryStorage, OwnedDeps,
-    };
-    use speculoos::{
-        assert_that, boolean::BooleanAssertions, option::OptionAssertions, result::ResultAssertions,
-    };
+    Ok(Response::default()
+        .add_messages(messages)
+        .add_attributes(vec![("action", "withdraw_treasury"), ("denom", denom)]))
+}
 
-    const TEST_TOKENS: [&str; 3] = ["magotchi1", "magotchi2", "magotchi3"];
+/// Revives a dead magotchi. Only the current owner of the token can do this, which is the
+/// graveyard once the magotchi has been reaped.
+pub fn execute_revive(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let revival = config
+        .revival
+        .as_ref()
+        .ok_or(ContractError::RevivalDisabled {})?;
 
-    fn prepare() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
-        let mut deps = mock_dependencies();
-        setup_contract(&mut deps.as_mut());
-        return deps;
+    let token = Cw721MetadataContract::default()
+        .tokens
+        .load(deps.storage, token_id)?;
+    if token.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
     }
 
-    fn setup_contract(deps: &mut DepsMut) {
-        let _ = CONFIG
-            .save(
-                deps.storage,
-                &Config {
-                    daily_feeding_cost: vec![Coin::new(1000, "uluna")],
-                    max_unfed_days: 10,
-                    feeding_cost_multiplier: 0,
-                    graveyard: Addr::unchecked("graveyard"),
-                },
-            )
-            .unwrap();
-
-        for token in TEST_TOKENS.iter() {
-            let _ = LIVE_STATES
-                .save(deps.storage, token.to_string(), &Gotchi::new())
-                .unwrap();
+    let mut state = load_gotchi(deps.storage, token_id)?;
+    if let Some(max) = revival.max_revivals {
+        if state.revivals() >= max {
+            return Err(ContractError::MaxRevivalsReached { max });
         }
     }
 
-    mod hatch {
-
-        use super::*;
+    let paying_coin = parse_funds(&info.funds)?;
+    let revival_cost = config.get_revival_cost(revival, &paying_coin.denom)?;
+    if paying_coin != revival_cost {
+        return Err(ContractError::InvalidPayment {
+            payed: paying_coin,
+            expected: revival_cost,
+        });
+    }
+    record_revenue(deps.storage, &config, &paying_coin)?;
 
-        #[test]
-        fn test_execute_hatch() {
-            let mut deps = prepare();
+    state.revive(&env.block, config.max_unfed_days_for(&state))?;
+    LIVE_STATES.save(deps.storage, token_id.to_string(), &state)?;
+    graves().remove(deps.storage, token_id.to_string())?;
 
-            // Mock environment and message info
-            let mut env = mock_env();
+    Ok(Response::default().add_attributes(vec![
+        ("action", "revive"),
+        ("token_id", token_id),
+        ("revivals", &state.revivals().to_string()),
+    ]))
+}
 
-            // Execute hatch
-            let res = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+/// Plays with, cleans or puts the magotchi to sleep, restoring one of its secondary stats. Only
+/// the owner or the borrower of the token can do this
+pub fn execute_care(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    action: CareAction,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let care = config.care.as_ref().ok_or(ContractError::CareDisabled {})?;
+    assert_owner_or_user(deps.as_ref(), env, info, token_id)?;
 
-            // Verify the response
-            assert_that!(res.attributes).is_equal_to(vec![
-                attr("action", "hatch"),
-                attr("token_id", "magotchi1"),
-                attr("birthday", env.block.time.to_string()),
-            ]);
+    let action_config = care.get(&action);
+    if !action_config.cost.is_empty() {
+        let paying_coin = assert_payment(&info.funds, &action_config.cost)?;
+        record_revenue(deps.storage, &config, &paying_coin)?;
+    }
 
-            // Verify the state changes
-            let state = LIVE_STATES
-                .load(&deps.storage, "magotchi1".to_string())
-                .unwrap();
-            assert_that!(state.is_hatched()).is_true();
-            assert_that!(state.is_dead(&env.block)).is_false();
-            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(1));
-            assert_that!(state.hatched_at())
-                .is_some()
-                .is_equal_to(env.block.time);
+    let mut state = touch_gotchi(deps.storage, &env.block, &config, token_id)?;
+    state.care(&env.block, &action, action_config)?;
+    LIVE_STATES.save(deps.storage, token_id.to_string(), &state)?;
 
-            // at the same time, the health is 2, but one second later, it should be 1
-            assert_that!(state.health(&env.block, 10)).is_equal_to(2);
-            env.block.time = env.block.time.plus_seconds(1);
-            assert_that!(state.health(&env.block, 10)).is_equal_to(1);
-        }
+    Ok(Response::default()
+        .add_attributes(vec![("action", action.as_str()), ("token_id", token_id)]))
+}
 
-        #[test]
-        fn test_execute_hatch_already_hatched() {
-            let mut deps = prepare();
+/// Buys items from the catalog into the inventory of the sender, paying the price of all of them
+pub fn execute_buy_item(
+    deps: &mut DepsMut,
+    info: &MessageInfo,
+    item: &str,
+    amount: u32,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    if amount == 0 {
+        return Err(ContractError::InvalidAmount {});
+    }
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let prices: Vec<Coin> = config
+        .get_item(item)?
+        .price
+        .iter()
+        .map(|coin| {
+            Ok(Coin {
+                denom: coin.denom.clone(),
+                amount: coin.amount.checked_mul(Uint128::from(amount))?,
+            })
+        })
+        .collect::<Result<_, OverflowError>>()
+        .map_err(StdError::from)?;
+    let paying_coin = assert_payment(&info.funds, &prices)?;
+    record_revenue(deps.storage, &config, &paying_coin)?;
 
-            // Mock environment and message info
-            let env = mock_env();
+    INVENTORIES.update(
+        deps.storage,
+        (&info.sender, item),
+        |owned| -> Result<_, ContractError> {
+            owned
+                .unwrap_or_default()
+                .checked_add(amount)
+                .ok_or(ContractError::InvalidAmount {})
+        },
+    )?;
 
-            // Hatch the magotchi first
-            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+    Ok(Response::default().add_attributes(vec![
+        ("action", "buy_item"),
+        ("item", item),
+        ("amount", &amount.to_string()),
+    ]))
+}
 
-            // Try to hatch again
-            let res = execute_hatch(&mut deps.as_mut(), &env, "magotchi1");
+/// Uses an item from the inventory of the sender on the magotchi. Food and medicine follow the
+/// feeding policy, revive potions can only be used by the owner of the dead magotchi
+pub fn execute_use_item(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    item: &str,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let effect = &config.get_item(item)?.effect;
 
-            // Verify the error
-            assert_that!(res).is_err();
-        }
+    let owned = INVENTORIES
+        .may_load(deps.storage, (&info.sender, item))?
+        .unwrap_or_default();
+    if owned == 0 {
+        return Err(ContractError::InsufficientItems {
+            item: item.to_string(),
+        });
     }
 
-    mod feed {
-        use super::*;
-
+    let mut state = touch_gotchi(deps.storage, &env.block, &config, token_id)?;
+    let max_unfed_days = config.max_unfed_days_for(&state);
+    match effect {
+        ItemEffect::Nourish { days } => {
+            assert_can_feed(deps.as_ref(), env, info, &config.feeding_policy, token_id)?;
+            state.nourish(&env.block, *days, max_unfed_days)?;
+        }
+        ItemEffect::Heal {} => {
+            assert_can_feed(deps.as_ref(), env, info, &config.feeding_policy, token_id)?;
+            state.cure();
+            state.feed(&env.block, config.max_unfed_days_for(&state))?;
+        }
+        ItemEffect::Revive {} => {
+            let token = Cw721MetadataContract::default()
+                .tokens
+                .load(deps.storage, token_id)?;
+            if token.owner != info.sender {
+                return Err(ContractError::Unauthorized {});
+            }
+            if let Some(max) = config.revival.as_ref().and_then(|r| r.max_revivals) {
+                if state.revivals() >= max {
+                    return Err(ContractError::MaxRevivalsReached { max });
+                }
+            }
+            state.revive(&env.block, max_unfed_days)?;
+            graves().remove(deps.storage, token_id.to_string())?;
+        }
+    }
+    LIVE_STATES.save(deps.storage, token_id.to_string(), &state)?;
+    INVENTORIES.save(deps.storage, (&info.sender, item), &(owned - 1))?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "use_item"),
+        ("token_id", token_id),
+        ("item", item),
+    ]))
+}
+
+/// Buys a vacation for the magotchi, pausing its hunger for the given number of days. Only the
+/// owner or the borrower of the token can do this
+pub fn execute_start_vacation(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    days: u32,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let vacation = config
+        .vacation
+        .as_ref()
+        .ok_or(ContractError::VacationDisabled {})?;
+    assert_owner_or_user(deps.as_ref(), env, info, token_id)?;
+
+    let paying_coin = parse_funds(&info.funds)?;
+    let vacation_cost = vacation.get_cost(days, &paying_coin.denom)?;
+    if paying_coin != vacation_cost {
+        return Err(ContractError::InvalidPayment {
+            payed: paying_coin,
+            expected: vacation_cost,
+        });
+    }
+    record_revenue(deps.storage, &config, &paying_coin)?;
+
+    let mut state = touch_gotchi(deps.storage, &env.block, &config, token_id)?;
+    state.start_vacation(&env.block, days, vacation)?;
+    LIVE_STATES.save(deps.storage, token_id.to_string(), &state)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "start_vacation"),
+        ("token_id", token_id),
+        ("days", &days.to_string()),
+    ]))
+}
+
+/// Breeds two adult magotchis, minting an unhatched egg to the sender. The sender must own or be
+/// approved for both parents
+pub fn execute_breed(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    parent_a: &str,
+    parent_b: &str,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let breeding = config
+        .breeding
+        .as_ref()
+        .ok_or(ContractError::BreedingDisabled {})?;
+
+    if parent_a == parent_b {
+        return Err(ContractError::CannotBreedWithItself {});
+    }
+
+    let paying_coin = parse_funds(&info.funds)?;
+    let fee = breeding.get_fee(&paying_coin.denom)?;
+    if paying_coin != fee {
+        return Err(ContractError::InvalidPayment {
+            payed: paying_coin,
+            expected: fee,
+        });
+    }
+    record_revenue(deps.storage, &config, &paying_coin)?;
+
+    let contract = Cw721MetadataContract::default();
+    let mut parents = vec![];
+    let mut genomes = vec![];
+    for parent in [parent_a, parent_b] {
+        let token = contract.tokens.load(deps.storage, parent)?;
+        contract.check_can_send(deps.as_ref(), env, info, &token)?;
+
+        let mut state = load_gotchi(deps.storage, parent)?;
+        state.breed(&env.block, breeding)?;
+        LIVE_STATES.save(deps.storage, parent.to_string(), &state)?;
+        parents.push(token.extension.unwrap_or_default());
+        genomes.push(state.genome().cloned());
+    }
+
+    let token_id = next_token_id(deps, &NEXT_EGG_ID, "egg")?;
+    let extension = child_metadata(env, parent_a, &parents[0], parent_b, &parents[1]);
+    mint_as_minter(deps, &token_id, &info.sender, Some(extension.clone()))?;
+    if let Some(genetics) = &config.genetics {
+        // the egg inherits the genes of its parents instead of getting random ones when it hatches
+        let genome = inherit_genome(
+            genetics,
+            &token_id,
+            &[genomes[0].as_ref(), genomes[1].as_ref()],
+            &extension.attributes.unwrap_or_default(),
+        );
+        let mut egg = load_gotchi(deps.storage, &token_id)?;
+        egg.set_genome(genome);
+        LIVE_STATES.save(deps.storage, token_id.clone(), &egg)?;
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "breed"),
+        ("parent_a", parent_a),
+        ("parent_b", parent_b),
+        ("token_id", &token_id),
+    ]))
+}
+
+/// Returns the next free token id with the given prefix, skipping ids that were minted by hand
+fn next_token_id(deps: &mut DepsMut, counter: &Item<u64>, prefix: &str) -> StdResult<String> {
+    let contract = Cw721MetadataContract::default();
+    let mut id = counter.may_load(deps.storage)?.unwrap_or_default();
+    let token_id = loop {
+        let token_id = format!("{prefix}-{id}");
+        id += 1;
+        if !contract.tokens.has(deps.storage, &token_id) {
+            break token_id;
+        }
+    };
+    counter.save(deps.storage, &id)?;
+    Ok(token_id)
+}
+
+/// Mints a token through the cw721 base contract on behalf of the minter, and initializes its
+/// live state
+fn mint_as_minter(
+    deps: &mut DepsMut,
+    token_id: &str,
+    owner: &Addr,
+    extension: Extension,
+) -> Result<(), ContractError> {
+    let minter = cw_ownable::get_ownership(deps.storage)?
+        .owner
+        .ok_or(cw_ownable::OwnershipError::NoOwner)?;
+    execute_mint(deps, token_id.to_string(), &extension)?;
+    Cw721MetadataContract::default().mint(
+        deps.branch(),
+        MessageInfo {
+            sender: minter,
+            funds: vec![],
+        },
+        token_id.to_string(),
+        owner.to_string(),
+        None,
+        extension,
+    )?;
+    Ok(())
+}
+
+/// Checks that exactly one of the prices was paid
+fn assert_payment(funds: &Vec<Coin>, prices: &[Coin]) -> Result<Coin, ContractError> {
+    let paying_coin = parse_funds(funds)?;
+    let price = prices
+        .iter()
+        .find(|coin| coin.denom == paying_coin.denom)
+        .ok_or(ContractError::InvalidDenom {
+            denom: paying_coin.denom.clone(),
+        })?;
+
+    if paying_coin != *price {
+        return Err(ContractError::InvalidPayment {
+            payed: paying_coin,
+            expected: price.clone(),
+        });
+    }
+    Ok(paying_coin)
+}
+
+/// Checks that the mint window is open
+fn assert_mint_window(
+    env: &Env,
+    start_time: Option<Timestamp>,
+    end_time: Option<Timestamp>,
+) -> Result<(), ContractError> {
+    if start_time.map_or(false, |start_time| env.block.time < start_time) {
+        return Err(ContractError::MintNotStarted {});
+    }
+    if end_time.map_or(false, |end_time| env.block.time >= end_time) {
+        return Err(ContractError::MintEnded {});
+    }
+    Ok(())
+}
+
+/// Mints a new magotchi to an address in the presale merkle tree, for the presale price
+pub fn execute_presale_mint(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    proof: &[String],
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let presale = config
+        .presale
+        .as_ref()
+        .ok_or(ContractError::MintDisabled {})?;
+    assert_mint_window(env, presale.start_time, presale.end_time)?;
+
+    if !verify_proof(&presale.merkle_root, info.sender.as_str(), proof) {
+        return Err(ContractError::InvalidMerkleProof {});
+    }
+
+    let supply = PRESALE_SUPPLY.may_load(deps.storage)?.unwrap_or_default();
+    if supply >= presale.max_supply {
+        return Err(ContractError::SoldOut {});
+    }
+
+    let minted = PRESALE_MINTED
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if minted >= presale.per_address_limit {
+        return Err(ContractError::MintLimitReached {
+            limit: presale.per_address_limit,
+        });
+    }
+
+    let paid = assert_payment(&info.funds, &presale.price)?;
+    record_revenue(deps.storage, &config, &paid)?;
+    PRESALE_MINTED.save(deps.storage, &info.sender, &(minted + 1))?;
+    PRESALE_SUPPLY.save(deps.storage, &(supply + 1))?;
+
+    let token_id = next_token_id(deps, &NEXT_TOKEN_ID, "token")?;
+    mint_as_minter(deps, &token_id, &info.sender, None)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "presale_mint"),
+        ("token_id", token_id.as_str()),
+        ("owner", info.sender.as_str()),
+    ]))
+}
+
+/// Mints a new magotchi to the sender for the public mint price
+pub fn execute_public_mint(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let public_mint = config
+        .public_mint
+        .as_ref()
+        .ok_or(ContractError::MintDisabled {})?;
+
+    assert_mint_window(env, public_mint.start_time, public_mint.end_time)?;
+
+    let supply = PUBLIC_SUPPLY.may_load(deps.storage)?.unwrap_or_default();
+    if supply >= public_mint.max_supply {
+        return Err(ContractError::SoldOut {});
+    }
+
+    let minted = PUBLIC_MINTED
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if minted >= public_mint.per_address_limit {
+        return Err(ContractError::MintLimitReached {
+            limit: public_mint.per_address_limit,
+        });
+    }
+
+    // the paid funds stay in the contract, and can be withdrawn with WithdrawFunds
+    let paid = assert_payment(&info.funds, &public_mint.price)?;
+    record_revenue(deps.storage, &config, &paid)?;
+    PUBLIC_MINTED.save(deps.storage, &info.sender, &(minted + 1))?;
+    PUBLIC_SUPPLY.save(deps.storage, &(supply + 1))?;
+
+    let token_id = next_token_id(deps, &NEXT_TOKEN_ID, "token")?;
+    mint_as_minter(deps, &token_id, &info.sender, None)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "public_mint"),
+        ("token_id", token_id.as_str()),
+        ("owner", info.sender.as_str()),
+    ]))
+}
+
+const LINEAGE_TRAITS: [&str; 3] = ["parent_a", "parent_b", "generation"];
+
+/// Records the lineage of the egg and lets it inherit every trait of its parents, picking one
+/// parent at random when both have the same trait
+fn child_metadata(
+    env: &Env,
+    parent_a: &str,
+    metadata_a: &Metadata,
+    parent_b: &str,
+    metadata_b: &Metadata,
+) -> Metadata {
+    let attributes_a = metadata_a.attributes.clone().unwrap_or_default();
+    let attributes_b = metadata_b.attributes.clone().unwrap_or_default();
+    let generation = |attributes: &[Trait]| {
+        attributes
+            .iter()
+            .find(|t| t.trait_type == "generation")
+            .and_then(|t| t.value.parse::<u64>().ok())
+            .unwrap_or_default()
+    };
+    let generation = generation(&attributes_a).max(generation(&attributes_b)) + 1;
+
+    let mut attributes = vec![
+        Trait {
+            display_type: None,
+            trait_type: "parent_a".to_string(),
+            value: parent_a.to_string(),
+        },
+        Trait {
+            display_type: None,
+            trait_type: "parent_b".to_string(),
+            value: parent_b.to_string(),
+        },
+        Trait {
+            display_type: Some("number".to_string()),
+            trait_type: "generation".to_string(),
+            value: generation.to_string(),
+        },
+    ];
+
+    let inheritable = |t: &&Trait| !LINEAGE_TRAITS.contains(&t.trait_type.as_str());
+    for (i, trait_a) in attributes_a.iter().filter(inheritable).enumerate() {
+        let trait_b = attributes_b
+            .iter()
+            .find(|t| t.trait_type == trait_a.trait_type);
+        let inherited = match trait_b {
+            Some(trait_b) if (env.block.height + i as u64) % 2 == 1 => trait_b,
+            _ => trait_a,
+        };
+        attributes.push(inherited.clone());
+    }
+    attributes.extend(
+        attributes_b
+            .iter()
+            .filter(inheritable)
+            .filter(|t| !attributes_a.iter().any(|a| a.trait_type == t.trait_type))
+            .cloned(),
+    );
+
+    Metadata {
+        description: Some(format!("The offspring of {parent_a} and {parent_b}")),
+        attributes: Some(attributes),
+        ..Metadata::default()
+    }
+}
+
+pub fn get_all_dead(deps: Deps, block: &BlockInfo) -> StdResult<Vec<String>> {
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    LIVE_STATES
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((token_id, mut state)) => {
+                state.catch_up_pauses(&pause.periods);
+                state.is_dead(block).then_some(Ok(token_id))
+            }
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
+pub fn assert_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    match PAUSE.may_load(storage)? {
+        Some(pause) if pause.is_paused() => Err(ContractError::ContractPaused {}),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the magotchis can be transferred. Dead magotchis can't be transferred until they
+/// are revived or reaped, hungry magotchis can't be transferred with the hunger tax, and the
+/// transfer modes of the cw721 base contract that depend on the state of the magotchis apply
+pub fn assert_transferable(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    token_ids: &[String],
+) -> Result<(), ContractError> {
+    let config = CONFIG.load(storage)?;
+    let mode = Cw721MetadataContract::default().transfer_mode(storage)?;
+    for token_id in token_ids {
+        let state = load_gotchi(storage, token_id)?;
+        let dead = state.is_dead(block);
+        if dead && !graves().has(storage, token_id.to_string()) {
+            return Err(ContractError::DeadNotTransferable {
+                token_id: token_id.to_string(),
+            });
+        }
+        // reaped magotchis can't be fed anymore, so they owe no tax
+        if !dead && config.hunger_tax && config.get_feeding_cost(&state, block) > 0 {
+            return Err(ContractError::HungerTaxDue {
+                token_id: token_id.to_string(),
+            });
+        }
+
+        let transferable = match mode {
+            TransferMode::WhileAlive => !state.is_dead(block),
+            TransferMode::WhileUnhatched => !state.is_hatched(),
+            _ => true,
+        };
+        if !transferable {
+            return Err(cw721_base::ContractError::NonTransferable {}.into());
+        }
+    }
+    Ok(())
+}
+
+/// Pays the hunger tax of a magotchi with the funds attached to its transfer, feeding it. Only
+/// hungry magotchis owe the tax, and only if it is enabled in the config
+pub fn execute_pay_hunger_tax(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = load_gotchi(deps.storage, token_id)?;
+    if !config.hunger_tax || config.get_feeding_cost(&state, &env.block) == 0 {
+        return Err(ContractError::NoHungerTaxDue {
+            token_id: token_id.to_string(),
+        });
+    }
+    execute_feed(deps, env, info, token_id)
+}
+
+/// Pauses the contract, freezing the death clock of every magotchi. Only the owner can do this
+pub fn execute_pause(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    if pause.is_paused() {
+        return Err(ContractError::ContractPaused {});
+    }
+    pause.paused_at = Some(env.block.time);
+    PAUSE.save(deps.storage, &pause)?;
+
+    Ok(Response::default().add_attributes(vec![("action", "pause")]))
+}
+
+/// Unpauses the contract. The death times are shifted by the paused duration when the magotchis
+/// are loaded next
+pub fn execute_unpause(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    let started_at = pause.paused_at.take().ok_or(ContractError::NotPaused {})?;
+    pause.periods.push(PausePeriod {
+        started_at,
+        ended_at: env.block.time,
+    });
+    PAUSE.save(deps.storage, &pause)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "unpause"),
+        (
+            "paused_seconds",
+            &(env.block.time.seconds() - started_at.seconds()).to_string(),
+        ),
+    ]))
+}
+
+/// Sets the royalties of a single magotchi, overriding the royalties of the config. Removes the
+/// override if no royalties are given. Only the contract owner can do this
+pub fn execute_set_token_royalties(
+    deps: &mut DepsMut,
+    info: &MessageInfo,
+    token_id: &str,
+    royalties: Option<RoyaltyConfig>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    Cw721MetadataContract::default()
+        .tokens
+        .load(deps.storage, token_id)?;
+
+    match royalties {
+        Some(royalties) => {
+            let marketplace = CONFIG.load(deps.storage)?.marketplace;
+            if !royalties.is_valid() || !royalties.fits_protocol_fee(marketplace.as_ref()) {
+                return Err(ContractError::InvalidConfig {});
+            }
+            TOKEN_ROYALTIES.save(deps.storage, token_id, &royalties)?;
+        }
+        None => TOKEN_ROYALTIES.remove(deps.storage, token_id),
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "set_token_royalties"),
+        ("token_id", token_id),
+    ]))
+}
+
+/// Lists the magotchi for sale, approving the contract to transfer it to the buyer. Listing it
+/// again replaces the listing. Only the owner of the token can do this
+pub fn execute_list(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    price: Coin,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    if CONFIG.load(deps.storage)?.marketplace.is_none() {
+        return Err(ContractError::MarketplaceDisabled {});
+    }
+    if price.amount.is_zero() {
+        return Err(ContractError::InvalidPrice {});
+    }
+
+    let contract = Cw721MetadataContract::default();
+    let token = contract.tokens.load(deps.storage, token_id)?;
+    if token.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    assert_transferable(deps.storage, &env.block, &[token_id.to_string()])?;
+
+    let expires = expires.unwrap_or_default();
+    contract._update_approvals(
+        deps.branch(),
+        env,
+        info,
+        env.contract.address.as_str(),
+        token_id,
+        true,
+        Some(expires),
+    )?;
+    listings().save(
+        deps.storage,
+        token_id,
+        &Listing {
+            seller: info.sender.clone(),
+            price: price.clone(),
+            expires,
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "list"),
+        ("token_id", token_id),
+        ("price", &price.to_string()),
+    ]))
+}
+
+/// Removes the magotchi from sale, revoking the approval of the contract. Only the owner of the
+/// token can do this
+pub fn execute_delist(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    let listing = load_listing(deps.storage, token_id)?;
+    if listing.seller != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Cw721MetadataContract::default()._update_approvals(
+        deps.branch(),
+        env,
+        info,
+        env.contract.address.as_str(),
+        token_id,
+        false,
+        None,
+    )?;
+    listings().remove(deps.storage, token_id)?;
+
+    Ok(Response::default().add_attributes(vec![("action", "delist"), ("token_id", token_id)]))
+}
+
+/// Buys a listed magotchi for its exact price. The royalties and the protocol fee are taken from
+/// the price, the rest goes to the seller
+pub fn execute_buy(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let marketplace = config
+        .marketplace
+        .as_ref()
+        .ok_or(ContractError::MarketplaceDisabled {})?;
+
+    let listing = load_listing(deps.storage, token_id)?;
+    if listing.expires.is_expired(&env.block) {
+        return Err(ContractError::ListingExpired {
+            token_id: token_id.to_string(),
+        });
+    }
+    let price = assert_payment(&info.funds, &[listing.price.clone()])?;
+    assert_transferable(deps.storage, &env.block, &[token_id.to_string()])?;
+
+    listings().remove(deps.storage, token_id)?;
+    clear_feeding_allowlists(deps.storage, &[token_id.to_string()])?;
+    end_rentals(deps.storage, &[token_id.to_string()]);
+    Cw721MetadataContract::default()._transfer_nft(
+        deps.branch(),
+        env,
+        &as_contract(env),
+        info.sender.as_str(),
+        token_id,
+    )?;
+    let payouts = pay_sale(
+        deps.storage,
+        &config,
+        marketplace,
+        token_id,
+        &listing.seller,
+        &price,
+    )?;
+
+    Ok(Response::default()
+        .add_messages(payouts)
+        .add_attributes(vec![
+            ("action", "buy"),
+            ("token_id", token_id),
+            ("seller", listing.seller.as_str()),
+            ("buyer", info.sender.as_str()),
+            ("price", &price.to_string()),
+        ]))
+}
+
+/// Splits the price of a sale between the royalties, the protocol fee, which is recorded as revenue,
+/// and the seller. Returns the payouts
+fn pay_sale(
+    storage: &mut dyn Storage,
+    config: &Config,
+    marketplace: &MarketplaceConfig,
+    token_id: &str,
+    seller: &Addr,
+    price: &Coin,
+) -> Result<Vec<BankMsg>, ContractError> {
+    let fee = price
+        .amount
+        .multiply_ratio(marketplace.protocol_fee, 1000u64);
+    record_revenue(storage, config, &Coin::new(fee.u128(), &price.denom))?;
+
+    let mut payouts = vec![];
+    let mut proceeds = price.amount.checked_sub(fee).map_err(StdError::from)?;
+    if let Some(royalties) = RoyaltyConfig::load(storage, token_id)? {
+        // the protocol fee may have been raised since the royalties of the token were set
+        let royalty = royalties.amount(price.amount).min(proceeds);
+        proceeds -= royalty;
+        if !royalty.is_zero() {
+            payouts.push(BankMsg::Send {
+                to_address: royalties.payment_address.into_string(),
+                amount: vec![Coin::new(royalty.u128(), &price.denom)],
+            });
+        }
+    }
+    if !proceeds.is_zero() {
+        payouts.push(BankMsg::Send {
+            to_address: seller.to_string(),
+            amount: vec![Coin::new(proceeds.u128(), &price.denom)],
+        });
+    }
+    Ok(payouts)
+}
+
+/// The message info of the contract itself, which is approved for listed magotchis and owns the
+/// magotchis in auction
+fn as_contract(env: &Env) -> MessageInfo {
+    MessageInfo {
+        sender: env.contract.address.clone(),
+        funds: vec![],
+    }
+}
+
+fn load_listing(storage: &dyn Storage, token_id: &str) -> Result<Listing, ContractError> {
+    listings()
+        .may_load(storage, token_id)?
+        .ok_or(ContractError::NotListed {
+            token_id: token_id.to_string(),
+        })
+}
+
+/// Removes the listings of magotchis that changed owner outside of the marketplace
+pub fn remove_listings(storage: &mut dyn Storage, token_ids: &[String]) -> StdResult<()> {
+    for token_id in token_ids {
+        listings().remove(storage, token_id)?;
+    }
+    Ok(())
+}
+
+/// Starts an english auction for the magotchi. The magotchi is held by the contract until the
+/// auction is settled. Only the owner of the token can do this
+pub fn execute_start_auction(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    reserve_price: Coin,
+    min_increment: Uint128,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    if CONFIG.load(deps.storage)?.marketplace.is_none() {
+        return Err(ContractError::MarketplaceDisabled {});
+    }
+    if reserve_price.amount.is_zero() || min_increment.is_zero() {
+        return Err(ContractError::InvalidPrice {});
+    }
+
+    let contract = Cw721MetadataContract::default();
+    let token = contract.tokens.load(deps.storage, token_id)?;
+    if token.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    assert_transferable(deps.storage, &env.block, &[token_id.to_string()])?;
+
+    remove_listings(deps.storage, &[token_id.to_string()])?;
+    clear_feeding_allowlists(deps.storage, &[token_id.to_string()])?;
+    end_rentals(deps.storage, &[token_id.to_string()]);
+    contract._transfer_nft(
+        deps.branch(),
+        env,
+        info,
+        env.contract.address.as_str(),
+        token_id,
+    )?;
+    let end_time = env.block.time.plus_seconds(duration);
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    AUCTIONS.save(
+        deps.storage,
+        token_id,
+        &Auction {
+            seller: info.sender.clone(),
+            reserve_price: reserve_price.clone(),
+            min_increment,
+            end_time,
+            highest_bid: None,
+            pauses_applied: pause.periods.len() as u32,
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "start_auction"),
+        ("token_id", token_id),
+        ("reserve_price", &reserve_price.to_string()),
+        ("end_time", &end_time.to_string()),
+    ]))
+}
+
+/// Bids on a magotchi in auction. The bid is held by the contract, the previous highest bidder is
+/// refunded. Bids shortly before the end extend the auction
+pub fn execute_bid(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let marketplace = config
+        .marketplace
+        .as_ref()
+        .ok_or(ContractError::MarketplaceDisabled {})?;
+
+    let mut auction = load_auction(deps.storage, token_id)?;
+    if auction.is_ended(&env.block) {
+        return Err(ContractError::AuctionEnded {
+            token_id: token_id.to_string(),
+        });
+    }
+    if auction.is_cancelled(deps.storage, &env.block, marketplace, token_id)? {
+        return Err(ContractError::AuctionCancelled {
+            token_id: token_id.to_string(),
+        });
+    }
+    if auction.seller == info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let bid = parse_funds(&info.funds)?;
+    let min_bid = auction.min_bid();
+    if bid.denom != min_bid.denom {
+        return Err(ContractError::InvalidDenom { denom: bid.denom });
+    }
+    if bid.amount < min_bid.amount {
+        return Err(ContractError::BidTooLow { min_bid });
+    }
+
+    // escrowed bids can't be withdrawn with the rest of the funds of the contract
+    let contract = Cw721MetadataContract::default();
+    contract.reserve_funds(deps.storage, &bid.denom, bid.amount)?;
+    let mut response = Response::default();
+    if let Some(outbid) = auction.highest_bid.replace(Bid {
+        bidder: info.sender.clone(),
+        amount: bid.clone(),
+    }) {
+        contract.release_funds(deps.storage, &outbid.amount.denom, outbid.amount.amount)?;
+        response = response.add_message(BankMsg::Send {
+            to_address: outbid.bidder.into_string(),
+            amount: vec![outbid.amount],
+        });
+    }
+
+    // a bid right before the end gives the other bidders time to respond
+    let extended_end = env.block.time.plus_seconds(marketplace.anti_snipe_seconds);
+    if extended_end > auction.end_time {
+        auction.end_time = extended_end;
+    }
+    AUCTIONS.save(deps.storage, token_id, &auction)?;
+
+    Ok(response.add_attributes(vec![
+        ("action", "bid"),
+        ("token_id", token_id),
+        ("bidder", info.sender.as_str()),
+        ("bid", &bid.to_string()),
+        ("end_time", &auction.end_time.to_string()),
+    ]))
+}
+
+/// Settles an auction. After the end, the magotchi goes to the highest bidder and the bid is paid
+/// out like a sale, or the magotchi is returned to the seller if there were no bids. Auctions of
+/// magotchis that died are cancelled right away if the marketplace cancels them. Anyone can do
+/// this, even if the marketplace has been disabled since
+pub fn execute_settle_auction(
+    deps: &mut DepsMut,
+    env: &Env,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let marketplace = config.marketplace.clone().unwrap_or_default();
+    let marketplace = &marketplace;
+
+    let auction = load_auction(deps.storage, token_id)?;
+    let cancelled = auction.is_cancelled(deps.storage, &env.block, marketplace, token_id)?;
+    if !cancelled && !auction.is_ended(&env.block) {
+        return Err(ContractError::AuctionNotEnded {
+            token_id: token_id.to_string(),
+        });
+    }
+    AUCTIONS.remove(deps.storage, token_id);
+    if let Some(bid) = &auction.highest_bid {
+        Cw721MetadataContract::default().release_funds(
+            deps.storage,
+            &bid.amount.denom,
+            bid.amount.amount,
+        )?;
+    }
+
+    let (recipient, payouts) = match auction.highest_bid {
+        Some(bid) if cancelled => (
+            auction.seller.clone(),
+            vec![BankMsg::Send {
+                to_address: bid.bidder.into_string(),
+                amount: vec![bid.amount],
+            }],
+        ),
+        Some(bid) => {
+            let payouts = pay_sale(
+                deps.storage,
+                &config,
+                marketplace,
+                token_id,
+                &auction.seller,
+                &bid.amount,
+            )?;
+            (bid.bidder, payouts)
+        }
+        None => (auction.seller.clone(), vec![]),
+    };
+    release_escrow(deps.storage, token_id, &recipient)?;
+
+    Ok(Response::default()
+        .add_messages(payouts)
+        .add_attributes(vec![
+            ("action", "settle_auction"),
+            ("token_id", token_id),
+            ("recipient", recipient.as_str()),
+            ("cancelled", &cancelled.to_string()),
+        ]))
+}
+
+/// Cancels an auction without bids, returning the magotchi to the seller. Only the seller can do
+/// this
+pub fn execute_cancel_auction(
+    deps: &mut DepsMut,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    let auction = load_auction(deps.storage, token_id)?;
+    if auction.seller != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if auction.highest_bid.is_some() {
+        return Err(ContractError::AuctionHasBids {
+            token_id: token_id.to_string(),
+        });
+    }
+
+    AUCTIONS.remove(deps.storage, token_id);
+    release_escrow(deps.storage, token_id, &auction.seller)?;
+
+    Ok(Response::default()
+        .add_attributes(vec![("action", "cancel_auction"), ("token_id", token_id)]))
+}
+
+/// Loads an auction, with its end time shifted by the global pauses since it started
+fn load_auction(storage: &dyn Storage, token_id: &str) -> Result<Auction, ContractError> {
+    let mut auction = AUCTIONS
+        .may_load(storage, token_id)?
+        .ok_or(ContractError::NotInAuction {
+            token_id: token_id.to_string(),
+        })?;
+    let pause = PAUSE.may_load(storage)?.unwrap_or_default();
+    auction.catch_up_pauses(&pause.periods);
+    Ok(auction)
+}
+
+/// Moves a magotchi out of the escrow of the contract. This doesn't depend on the transfer mode,
+/// which may have changed while the magotchi was in auction
+fn release_escrow(storage: &mut dyn Storage, token_id: &str, recipient: &Addr) -> StdResult<()> {
+    let contract = Cw721MetadataContract::default();
+    let mut token = contract.tokens.load(storage, token_id)?;
+    token.owner = recipient.clone();
+    token.approvals = vec![];
+    contract.tokens.save(storage, token_id, &token)
+}
+
+/// Lends the magotchi to the borrower until the rental expires. The borrower can feed and care for
+/// it, but only the owner can transfer it. With a price, the rental starts once the borrower paid
+/// it. Only the owner of the token can do this, as long as it isn't rented out
+pub fn execute_lend(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    to: &str,
+    until: Expiration,
+    price: Option<Coin>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let token = Cw721MetadataContract::default()
+        .tokens
+        .load(deps.storage, token_id)?;
+    if token.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if active_user(deps.storage, &env.block, token_id)?.is_some() {
+        return Err(ContractError::RentalActive {
+            token_id: token_id.to_string(),
+        });
+    }
+    if matches!(until, Expiration::Never {}) {
+        return Err(ContractError::RentalNeverExpires {});
+    }
+    if until.is_expired(&env.block) {
+        return Err(cw721_base::ContractError::Expired {}.into());
+    }
+    if price.as_ref().map_or(false, |price| price.amount.is_zero()) {
+        return Err(ContractError::InvalidPrice {});
+    }
+
+    let user = deps.api.addr_validate(to)?;
+    RENTALS.save(
+        deps.storage,
+        token_id,
+        &Rental {
+            user: Approval {
+                spender: user,
+                expires: until,
+            },
+            price,
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "lend"),
+        ("token_id", token_id),
+        ("to", to),
+        ("until", &until.to_string()),
+    ]))
+}
+
+/// Starts a rental by paying its price to the owner. Only the borrower can do this
+pub fn execute_rent(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let mut rental = RENTALS
+        .may_load(deps.storage, token_id)?
+        .ok_or(ContractError::NotRented {
+            token_id: token_id.to_string(),
+        })?;
+    if rental.user.spender != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if rental.user.is_expired(&env.block) {
+        return Err(cw721_base::ContractError::Expired {}.into());
+    }
+    let price = rental.price.take().ok_or(ContractError::RentalActive {
+        token_id: token_id.to_string(),
+    })?;
+    let payment = assert_payment(&info.funds, &[price])?;
+    RENTALS.save(deps.storage, token_id, &rental)?;
+
+    let token = Cw721MetadataContract::default()
+        .tokens
+        .load(deps.storage, token_id)?;
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: token.owner.into_string(),
+            amount: vec![payment],
+        })
+        .add_attributes(vec![
+            ("action", "rent"),
+            ("token_id", token_id),
+            ("user", info.sender.as_str()),
+        ]))
+}
+
+/// Ends a rental. The borrower can end it at any time, the owner only once it expired or if it
+/// was never paid
+pub fn execute_end_rental(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    let rental = RENTALS
+        .may_load(deps.storage, token_id)?
+        .ok_or(ContractError::NotRented {
+            token_id: token_id.to_string(),
+        })?;
+    let token = Cw721MetadataContract::default()
+        .tokens
+        .load(deps.storage, token_id)?;
+    if rental.user.spender != info.sender {
+        if token.owner != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        if rental.is_active(&env.block) {
+            return Err(ContractError::RentalActive {
+                token_id: token_id.to_string(),
+            });
+        }
+    }
+    RENTALS.remove(deps.storage, token_id);
+
+    Ok(Response::default().add_attributes(vec![("action", "end_rental"), ("token_id", token_id)]))
+}
+
+/// Checks that the sender owns the magotchi or is its borrower
+pub fn assert_owner_or_user(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    let token = Cw721MetadataContract::default()
+        .tokens
+        .load(deps.storage, token_id)?;
+    if token.owner == info.sender
+        || active_user(deps.storage, &env.block, token_id)?.as_ref() == Some(&info.sender)
+    {
+        return Ok(());
+    }
+    Err(ContractError::Unauthorized {})
+}
+
+/// Commits to the metadata of a batch of mystery boxes, so it can't be changed before the reveal.
+/// Only the contract owner can do this
+pub fn execute_commit_reveal(
+    deps: &mut DepsMut,
+    info: &MessageInfo,
+    commitment: &str,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let commitment = decode_hash(commitment).ok_or(ContractError::InvalidCommitment {})?;
+    let commitment = hex::encode(commitment);
+    REVEAL_COMMITMENTS.save(deps.storage, &commitment, &Empty {})?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "commit_reveal"),
+        ("commitment", &commitment),
+    ]))
+}
+
+/// Reveals the metadata of a batch of mystery boxes. Anyone can do this, as long as the batch
+/// matches a commitment
+pub fn execute_reveal(
+    deps: &mut DepsMut,
+    token_ids: Vec<String>,
+    metadata: Vec<Metadata>,
+) -> Result<Response, ContractError> {
+    if token_ids.len() != metadata.len() {
+        return Err(ContractError::InvalidReveal {});
+    }
+    let batch = RevealBatch {
+        token_ids,
+        metadata,
+    };
+    let commitment = batch.commitment()?;
+    if !REVEAL_COMMITMENTS.has(deps.storage, &commitment) {
+        return Err(ContractError::InvalidReveal {});
+    }
+    let contract = Cw721MetadataContract::default();
+    if contract.is_metadata_frozen(deps.storage)? {
+        return Err(cw721_base::ContractError::MetadataFrozen {}.into());
+    }
+
+    for (token_id, mut metadata) in batch.token_ids.iter().zip(batch.metadata) {
+        if !UNREVEALED.has(deps.storage, token_id) {
+            return Err(ContractError::AlreadyRevealed {
+                token_id: token_id.to_string(),
+            });
+        }
+        UNREVEALED.remove(deps.storage, token_id);
+
+        // magotchis that hatched before the reveal keep their traits
+        if let Some(genome) = load_gotchi(deps.storage, token_id)?.genome() {
+            metadata.attributes = Some(merge_traits(metadata.attributes, &genome.traits));
+        }
+        let mut token = contract.tokens.load(deps.storage, token_id)?;
+        token.extension = Some(metadata);
+        contract.tokens.save(deps.storage, token_id, &token)?;
+    }
+    REVEAL_COMMITMENTS.remove(deps.storage, &commitment);
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "reveal"),
+        ("commitment", &commitment),
+        ("revealed", &batch.token_ids.len().to_string()),
+    ]))
+}
+
+/// Initializes the live state of a minted token. Tokens minted without metadata are mystery boxes
+/// if a reveal is configured
+pub fn execute_mint(
+    deps: &mut DepsMut,
+    token_id: String,
+    extension: &Extension,
+) -> Result<(), ContractError> {
+    if extension.is_none() && CONFIG.load(deps.storage)?.reveal.is_some() {
+        UNREVEALED.save(deps.storage, &token_id, &Empty {})?;
+    }
+    LIVE_STATES
+        .save(deps.storage, token_id.to_string(), &Gotchi::new())
+        .map_err(Into::into)
+}
+
+/// Updates the given fields of the config. Only the owner can do this
+pub fn execute_update_config(
+    deps: &mut DepsMut,
+    info: &MessageInfo,
+    partial_config: PartialConfig,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    CONFIG.update(deps.storage, |mut config| -> Result<_, ContractError> {
+        if let Some(daily_feeding_cost) = partial_config.daily_feeding_cost {
+            config.daily_feeding_cost = daily_feeding_cost;
+        }
+        if let Some(max_unfed_days) = partial_config.max_unfed_days {
+            config.max_unfed_days = max_unfed_days;
+        }
+        if let Some(feeding_cost_multiplier) = partial_config.feeding_cost_multiplier {
+            config.feeding_cost_multiplier = feeding_cost_multiplier;
+        }
+        if let Some(caretaker_rewards) = partial_config.caretaker_rewards {
+            config.caretaker_rewards = caretaker_rewards;
+        }
+        if let Some(feeding_policy) = partial_config.feeding_policy {
+            config.feeding_policy = feeding_policy;
+        }
+        if let Some(revival) = partial_config.revival {
+            config.revival = revival;
+        }
+        if let Some(vacation) = partial_config.vacation {
+            config.vacation = vacation;
+        }
+        if let Some(breeding) = partial_config.breeding {
+            config.breeding = breeding;
+        }
+        if let Some(genetics) = partial_config.genetics {
+            config.genetics = genetics;
+        }
+        if let Some(public_mint) = partial_config.public_mint {
+            config.public_mint = public_mint;
+        }
+        if let Some(presale) = partial_config.presale {
+            config.presale = presale;
+        }
+        if let Some(treasury) = partial_config.treasury {
+            config.treasury = treasury;
+        }
+        if let Some(hunger_tax) = partial_config.hunger_tax {
+            config.hunger_tax = hunger_tax;
+        }
+        if let Some(royalties) = partial_config.royalties {
+            config.royalties = royalties;
+        }
+        if let Some(reveal) = partial_config.reveal {
+            config.reveal = reveal;
+        }
+        if let Some(marketplace) = partial_config.marketplace {
+            config.marketplace = marketplace;
+        }
+        if let Some(care) = partial_config.care {
+            config.care = care;
+        }
+        if let Some(items) = partial_config.items {
+            config.items = items;
+        }
+        if let Some(sickness) = partial_config.sickness {
+            config.sickness = sickness;
+        }
+        if let Some(randomness) = partial_config.randomness {
+            config.randomness = randomness;
+        }
+        config.validate()?;
+
+        Ok(config)
+    })?;
+
+    Ok(Response::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::Config;
+    use crate::{ExecuteMsg, CONTRACT_NAME};
+
+    use super::*;
+    use cosmwasm_std::{
+        attr, coin, coins,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier},
+        Addr, CosmosMsg, MemoryStorage, OwnedDeps, Timestamp,
+    };
+    use cw721_base::InstantiateMsg;
+    use speculoos::{
+        assert_that, boolean::BooleanAssertions, option::OptionAssertions,
+        result::ResultAssertions, vec::VecAssertions,
+    };
+
+    const TEST_TOKENS: [&str; 3] = ["magotchi1", "magotchi2", "magotchi3"];
+
+    fn prepare() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        setup_contract(&mut deps.as_mut());
+        return deps;
+    }
+
+    fn setup_contract(deps: &mut DepsMut) {
+        let _ = CONFIG
+            .save(
+                deps.storage,
+                &Config {
+                    daily_feeding_cost: vec![Coin::new(1000, "uluna")],
+                    max_unfed_days: 10,
+                    feeding_cost_multiplier: 0,
+                    graveyard: Addr::unchecked("graveyard"),
+                    caretaker_rewards: None,
+                    feeding_policy: FeedingPolicy::Open,
+                    hunger_tax: false,
+                    revival: None,
+                    vacation: None,
+                    breeding: None,
+                    genetics: None,
+                    public_mint: None,
+                    presale: None,
+                    treasury: None,
+                    royalties: None,
+                    reveal: None,
+                    marketplace: None,
+                    care: None,
+                    items: vec![],
+                    sickness: None,
+                    randomness: None,
+                },
+            )
+            .unwrap();
+
+        for token in TEST_TOKENS.iter() {
+            let _ = LIVE_STATES
+                .save(deps.storage, token.to_string(), &Gotchi::new())
+                .unwrap();
+        }
+    }
+
+    const SYMBOL: &str = "MAG";
+    const MINTER: &str = "minter";
+    const OWNER: &str = "test_user";
+
+    fn mint_msg(token_id: &str) -> ExecuteMsg {
+        ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: OWNER.to_string(),
+            token_uri: None,
+            extension: None,
+        }
+    }
+
+    fn setup_cw721_base() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let contract = Cw721MetadataContract::default();
+        let msg = InstantiateMsg {
+            name: CONTRACT_NAME.to_string(),
+            symbol: SYMBOL.to_string(),
+            minter: Some(String::from(MINTER)),
+            withdraw_address: None,
+        };
+
+        let info = mock_info("creator", &[]);
+        let res = contract
+            .instantiate(deps.as_mut(), mock_env(), info, msg)
+            .unwrap();
+        assert_eq!(0, res.messages.len());
+
+        for token in TEST_TOKENS.iter() {
+            let msg = mint_msg(token);
+            let info = mock_info(MINTER, &[]);
+            let _ = contract
+                .execute(deps.as_mut(), mock_env(), info, msg)
+                .unwrap();
+        }
+        return deps;
+    }
+
+    fn prepare_cw721_base_state() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        let mut deps = setup_cw721_base();
+        setup_contract(&mut deps.as_mut());
+        return deps;
+    }
+
+    mod hatch {
+
+        use super::*;
+
+        #[test]
+        fn test_execute_hatch() {
+            let mut deps = prepare();
+
+            // Mock environment and message info
+            let mut env = mock_env();
+
+            // Execute hatch
+            let res = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            // Verify the response
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "hatch"),
+                attr("token_id", "magotchi1"),
+                attr("birthday", env.block.time.to_string()),
+            ]);
+
+            // Verify the state changes
+            let state = LIVE_STATES
+                .load(&deps.storage, "magotchi1".to_string())
+                .unwrap();
+            assert_that!(state.is_hatched()).is_true();
+            assert_that!(state.is_dead(&env.block)).is_false();
+            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(1));
+            assert_that!(state.hatched_at())
+                .is_some()
+                .is_equal_to(env.block.time);
+
+            // at the same time, the health is 2, but one second later, it should be 1
+            assert_that!(state.health(&env.block, 10)).is_equal_to(2);
+            env.block.time = env.block.time.plus_seconds(1);
+            assert_that!(state.health(&env.block, 10)).is_equal_to(1);
+        }
+
+        #[test]
+        fn test_execute_hatch_already_hatched() {
+            let mut deps = prepare();
+
+            // Mock environment and message info
+            let env = mock_env();
+
+            // Hatch the magotchi first
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            // Try to hatch again
+            let res = execute_hatch(&mut deps.as_mut(), &env, "magotchi1");
+
+            // Verify the error
+            assert_that!(res).is_err();
+        }
+    }
+
+    mod feed {
+        use super::*;
+        use crate::state::SicknessConfig;
+
+        #[test]
+        fn test_sickness_during_feed() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.sickness = Some(SicknessConfig {
+                        min_days_unfed: 3,
+                        chance: 1000,
+                        multiplier: 2000,
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            // the feeder pays the cost from before the roll, which makes the magotchi sick
+            let info = mock_info("feeder", &coins(8_000_000, "uluna"));
+            let _ = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+            let state = load_gotchi(&deps.storage, "magotchi1").unwrap();
+            assert_that!(state.is_sick()).is_true();
+            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(5));
+        }
+
+        #[test]
+        fn test_execute_feed() {
+            let mut deps = prepare();
+
+            // Mock environment and message info
+            let env = mock_env();
+
+            // Hatch the magotchi first
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            // Execute feed. After hatch, the magotchi is unfed for 9 days.
+            let info = mock_info("feeder", &coins(8_000_000, "uluna"));
+            let res = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+
+            // Verify the response
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "feed"),
+                attr("token_id", "magotchi1"),
+                attr("feeder", "feeder"),
+            ]);
+
+            // Verify the state changes
+            let state = LIVE_STATES
+                .load(&deps.storage, "magotchi1".to_string())
+                .unwrap();
+            assert_that!(state.is_hatched()).is_true();
+            assert_that!(state.is_dead(&env.block)).is_false();
+            assert_that!(state.hatched_at())
+                .is_some()
+                .is_equal_to(env.block.time);
+
+            // Verify the new death time
+            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(10));
+        }
+
+        #[test]
+        fn test_execute_feed_insufficient_funds() {
+            let mut deps = prepare();
+
+            // Mock environment and message info
+            let env = mock_env();
+            let info = mock_info("feeder", &coins(500, "uluna")); // Insufficient funds
+
+            // Hatch the magotchi first
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            // Execute feed
+            let res = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1");
+
+            // Verify the error
+            assert_that!(res).is_err();
+        }
+
+        #[test]
+        fn test_execute_feed_unhatched() {
+            let mut deps = prepare();
+
+            // Mock environment and message info
+            let env = mock_env();
+            let info = mock_info("feeder", &coins(1000, "uluna"));
+
+            // Execute feed on an unhatched magotchi
+            let res = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1");
+
+            // Verify the error
+            assert_that!(res).is_err();
+        }
+
+        #[test]
+        fn test_execute_feed_dead() {
+            let mut deps = prepare();
+
+            // Mock environment and message info
+            let mut env = mock_env();
+            let info = mock_info("feeder", &coins(1000, "uluna"));
+
+            // Hatch the magotchi and simulate it being dead
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            env.block.time = env.block.time.plus_days(11); // Simulate time passing
+
+            // Execute feed
+            let res = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1");
+
+            // Verify the error
+            assert_that!(res).is_err();
+        }
+
+        #[test]
+        fn test_execute_feed_wrong_denomination() {
+            let mut deps = prepare();
+
+            // Mock environment and message info
+            let env = mock_env();
+            let info = mock_info("feeder", &coins(1000, "uusd")); // Wrong denomination
+
+            // Hatch the magotchi first
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            // Execute feed
+            let res = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1");
+
+            // Verify the error
+            assert_that!(res).is_err();
+        }
+
+        #[test]
+        fn test_execute_feed_exact_funds() {
+            let mut deps = prepare();
+
+            // Mock environment and message info
+            let env = mock_env();
+            let info = mock_info("feeder", &coins(8_000_000, "uluna"));
+
+            // Hatch the magotchi first
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            // Execute feed
+            let res = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+
+            // Verify the response
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "feed"),
+                attr("token_id", "magotchi1"),
+                attr("feeder", "feeder"),
+            ]);
+
+            // Verify the state changes
+            let state = LIVE_STATES
+                .load(&deps.storage, "magotchi1".to_string())
+                .unwrap();
+            assert_that!(state.is_hatched()).is_true();
+            assert_that!(state.is_dead(&env.block)).is_false();
+            assert_that!(state.hatched_at())
+                .is_some()
+                .is_equal_to(env.block.time);
+
+            // Verify the new death time
+            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(10));
+        }
+
+        #[test]
+        fn test_execute_feed_records_caretakers() {
+            let mut deps = prepare();
+            let mut env = mock_env();
+
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            let info = mock_info("feeder", &coins(8_000_000, "uluna"));
+            let _ = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+
+            // two days later the magotchi is unfed for 1 day
+            env.block.time = env.block.time.plus_days(2);
+            let _ = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+
+            let caretaker = CARETAKERS
+                .load(&deps.storage, ("magotchi1", &Addr::unchecked("feeder")))
+                .unwrap();
+            assert_that!(caretaker).is_equal_to(Caretaker {
+                feedings: 2,
+                contribution: 9000,
+            });
+        }
+
+        #[test]
+        fn test_execute_feed_rewards_caretakers() {
+            let mut deps = prepare();
+            let mut env = mock_env();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.caretaker_rewards = Some(crate::state::CaretakerRewards {
+                        share: 100,
+                        min_feedings: 1,
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            // nobody to reward yet
+            let info = mock_info("caretaker", &coins(8_000_000, "uluna"));
+            let res = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+            assert_that!(res.messages).is_empty();
+
+            // the caretaker does not reward itself
+            env.block.time = env.block.time.plus_days(2);
+            let info = mock_info("caretaker", &coins(1_000_000, "uluna"));
+            let res = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+            assert_that!(res.messages).is_empty();
+
+            // another feeder pays 10% to the long-term caretaker
+            env.block.time = env.block.time.plus_days(2);
+            let info = mock_info("stranger", &coins(1_000_000, "uluna"));
+            let res = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+            assert_that!(res.messages).has_length(1);
+            assert_that!(res.messages[0].msg).is_equal_to(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "caretaker".to_string(),
+                amount: coins(100_000, "uluna"),
+            }));
+        }
+
+        #[test]
+        fn test_top_caretakers_are_bounded() {
+            let mut deps = prepare();
+            for index in 0..MAX_TOP_CARETAKERS as u64 + 5 {
+                let feeder = Addr::unchecked(format!("feeder{index:02}"));
+                CARETAKERS
+                    .save(
+                        &mut deps.storage,
+                        ("magotchi1", &feeder),
+                        &Caretaker {
+                            feedings: 1,
+                            contribution: index,
+                        },
+                    )
+                    .unwrap();
+                update_top_caretakers(&mut deps.storage, "magotchi1", &feeder).unwrap();
+            }
+
+            let top = TOP_CARETAKERS.load(&deps.storage, "magotchi1").unwrap();
+            assert_that!(top).has_length(MAX_TOP_CARETAKERS);
+            assert_that!(top[0]).is_equal_to(Addr::unchecked("feeder24"));
+            assert_that!(top[MAX_TOP_CARETAKERS - 1]).is_equal_to(Addr::unchecked("feeder05"));
+
+            let page = crate::query::query_top_caretakers(
+                deps.as_ref(),
+                "magotchi1".to_string(),
+                Some("feeder23".to_string()),
+                Some(2),
+            )
+            .unwrap();
+            let addresses: Vec<String> = page.caretakers.into_iter().map(|c| c.address).collect();
+            assert_that!(addresses)
+                .is_equal_to(vec!["feeder22".to_string(), "feeder21".to_string()]);
+        }
+    }
+
+    mod feeding_policy {
+        use super::*;
+
+        fn prepare_with_policy(
+            policy: FeedingPolicy,
+        ) -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+            let mut deps = prepare_cw721_base_state();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.feeding_policy = policy;
+                    Ok(config)
+                })
+                .unwrap();
+            let _ = execute_hatch(&mut deps.as_mut(), &mock_env(), "magotchi1").unwrap();
+            deps
+        }
+
+        #[test]
+        fn test_owner_and_approved() {
+            let mut deps = prepare_with_policy(FeedingPolicy::OwnerAndApproved);
+            let env = mock_env();
+
+            let info = mock_info("stranger", &coins(8_000_000, "uluna"));
+            assert_that!(execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::NotAllowedToFeed {});
+
+            let info = mock_info(OWNER, &coins(8_000_000, "uluna"));
+            assert_that!(execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1")).is_ok();
+        }
+
+        #[test]
+        fn test_allowlist() {
+            let mut deps = prepare_with_policy(FeedingPolicy::Allowlist);
+            let env = mock_env();
+            let info = mock_info("friend", &coins(8_000_000, "uluna"));
+
+            assert_that!(execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::NotAllowedToFeed {});
+
+            // only the owner can manage the allowlist
+            assert_that!(execute_update_feeding_allowlist(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("friend", &[]),
+                "magotchi1",
+                "friend",
+                true,
+            ))
+            .is_err();
+
+            let res = execute_update_feeding_allowlist(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                "friend",
+                true,
+            )
+            .unwrap();
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "allow_feeder"),
+                attr("token_id", "magotchi1"),
+                attr("feeder", "friend"),
+            ]);
+            assert_that!(execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1")).is_ok();
+
+            let _ = execute_update_feeding_allowlist(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                "friend",
+                false,
+            )
+            .unwrap();
+            assert_that!(execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::NotAllowedToFeed {});
+        }
+
+        #[test]
+        fn test_allowlist_is_cleared_on_transfer() {
+            let mut deps = prepare_with_policy(FeedingPolicy::Allowlist);
+            let env = mock_env();
+            let _ = execute_update_feeding_allowlist(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                "friend",
+                true,
+            )
+            .unwrap();
+
+            crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(OWNER, &[]),
+                ExecuteMsg::TransferNft {
+                    recipient: "buyer".to_string(),
+                    token_id: "magotchi1".to_string(),
+                },
+            )
+            .unwrap();
+
+            // the buyer doesn't inherit the feeders of the previous owner
+            let info = mock_info("friend", &coins(8_000_000, "uluna"));
+            assert_that!(execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::NotAllowedToFeed {});
+        }
+    }
+
+    mod reap {
+        use super::*;
+
+        #[test]
+        fn test_execute_reap() {
+            let mut deps = prepare_cw721_base_state();
+
+            // Mock environment and message info
+            let mut env = mock_env();
+
+            // Hatch the magotchis and simulate them being dead
+            for &token in TEST_TOKENS.iter() {
+                let _ = execute_hatch(&mut deps.as_mut(), &env, token).unwrap();
+                let state = LIVE_STATES.load(&deps.storage, token.to_string()).unwrap();
+                let state = Gotchi::custom_min_1sec(
+                    state.hatched_at().unwrap().seconds() / (24 * 60 * 60),
+                    env.block.time.plus_seconds(1).seconds() / (24 * 60 * 60),
+                );
+                LIVE_STATES
+                    .save(&mut deps.storage, token.to_string(), &state)
+                    .unwrap();
+            }
+            env.block.time = env.block.time.plus_days(2); // Simulate time passing
+
+            // Execute reap
+            let res =
+                execute_reap(&mut deps.as_mut(), None, &env, &mock_info("reaper", &[])).unwrap();
+
+            // Verify the response
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "reap"),
+                attr("tokens", "magotchi1,magotchi2,magotchi3"),
+            ]);
+
+            // Verify the state changes
+            for &token in TEST_TOKENS.iter() {
+                let state = LIVE_STATES.load(&deps.storage, token.to_string()).unwrap();
+                assert_that!(state.is_dead(&env.block)).is_true();
+
+                let grave = graves().load(&deps.storage, token.to_string()).unwrap();
+                assert_that!(grave).is_equal_to(Grave {
+                    former_owner: Addr::unchecked(OWNER),
+                    died_at: state.death_time(),
+                    reaped_at: env.block.time,
+                });
+            }
+
+            // reaping again keeps the former owner
+            env.block.time = env.block.time.plus_days(1);
+            let _ =
+                execute_reap(&mut deps.as_mut(), None, &env, &mock_info("reaper", &[])).unwrap();
+            let grave = graves()
+                .load(&deps.storage, "magotchi1".to_string())
+                .unwrap();
+            assert_that!(grave.former_owner).is_equal_to(Addr::unchecked(OWNER));
+        }
+
+        #[test]
+        fn test_execute_reap_not_all_dead() {
+            let mut deps = prepare();
+
+            // Mock environment and message info
+            let mut env = mock_env();
+
+            // Hatch the magotchis and simulate only some being dead
+            for &token in TEST_TOKENS[..2].iter() {
+                let _ = execute_hatch(&mut deps.as_mut(), &env, token).unwrap();
+                let state = LIVE_STATES.load(&deps.storage, token.to_string()).unwrap();
+                let state = Gotchi::custom_min_1sec(
+                    state.hatched_at().unwrap().seconds() / (24 * 60 * 60),
+                    env.block.time.plus_seconds(1).seconds() / (24 * 60 * 60),
+                );
+                LIVE_STATES
+                    .save(&mut deps.storage, token.to_string(), &state)
+                    .unwrap();
+                env.block.time = env.block.time.plus_days(2); // Simulate time passing
+
+                // Execute reap
+                let res = execute_reap(&mut deps.as_mut(), None, &env, &mock_info("reaper", &[]));
+
+                // Verify the error
+                assert_that!(res).is_err();
+            }
+        }
+
+        #[test]
+        fn test_execute_reap_empty_tokens() {
+            let mut deps = prepare();
+
+            // Mock environment and message info
+            let env = mock_env();
+
+            // Execute reap with empty tokens list
+            let res = execute_reap(
+                &mut deps.as_mut(),
+                Some(vec![]),
+                &env,
+                &mock_info("reaper", &[]),
+            )
+            .unwrap();
+
+            // Verify the response
+            assert_that!(res.attributes)
+                .is_equal_to(vec![attr("action", "reap"), attr("tokens", "")]);
+        }
+
+        #[test]
+        fn test_execute_reap_some_dead() {
+            let mut deps = prepare_cw721_base_state();
+
+            // Mock environment and message info
+            let mut env = mock_env();
+
+            // Hatch the magotchis and simulate only some being dead
+            for &token in TEST_TOKENS[..2].iter() {
+                let _ = execute_hatch(&mut deps.as_mut(), &env, token).unwrap();
+                let state = LIVE_STATES.load(&deps.storage, token.to_string()).unwrap();
+                let state = Gotchi::custom_min_1sec(
+                    state.hatched_at().unwrap().seconds() / (24 * 60 * 60),
+                    env.block.time.plus_seconds(1).seconds() / (24 * 60 * 60),
+                );
+                LIVE_STATES
+                    .save(&mut deps.storage, token.to_string(), &state)
+                    .unwrap();
+            }
+            env.block.time = env.block.time.plus_days(2); // Simulate time passing
+
+            // Execute reap
+            let res = execute_reap(
+                &mut deps.as_mut(),
+                Some(vec!["magotchi1".to_string(), "magotchi2".to_string()]),
+                &env,
+                &mock_info("reaper", &[]),
+            )
+            .unwrap();
+
+            // Verify the response
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "reap"),
+                attr("tokens", "magotchi1,magotchi2"),
+            ]);
+        }
+    }
+
+    mod revive {
+        use super::*;
+        use crate::state::RevivalConfig;
+
+        fn prepare_dead(
+            revival: Option<RevivalConfig>,
+        ) -> (OwnedDeps<MemoryStorage, MockApi, MockQuerier>, Env) {
+            let mut deps = prepare_cw721_base_state();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.revival = revival;
+                    Ok(config)
+                })
+                .unwrap();
+
+            let mut env = mock_env();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            env.block.time = env.block.time.plus_days(2);
+            (deps, env)
+        }
+
+        #[test]
+        fn test_execute_revive_disabled() {
+            let (mut deps, env) = prepare_dead(None);
+            let info = mock_info(OWNER, &coins(20_000_000, "uluna"));
+
+            assert_that!(execute_revive(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::RevivalDisabled {});
+        }
+
+        #[test]
+        fn test_execute_revive() {
+            let (mut deps, env) = prepare_dead(Some(RevivalConfig {
+                cost_multiplier: 2,
+                max_revivals: None,
+            }));
+
+            // only the owner can revive
+            let info = mock_info("someone", &coins(20_000_000, "uluna"));
+            assert_that!(execute_revive(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::Unauthorized {});
+
+            // the revival cost is twice the cost of max_unfed_days without food
+            let info = mock_info(OWNER, &coins(10_000_000, "uluna"));
+            assert_that!(execute_revive(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::InvalidPayment {
+                    payed: coin(10_000_000, "uluna"),
+                    expected: coin(20_000_000, "uluna"),
+                });
+
+            let info = mock_info(OWNER, &coins(20_000_000, "uluna"));
+            let res = execute_revive(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "revive"),
+                attr("token_id", "magotchi1"),
+                attr("revivals", "1"),
+            ]);
+
+            let state = LIVE_STATES
+                .load(&deps.storage, "magotchi1".to_string())
+                .unwrap();
+            assert_that!(state.is_dead(&env.block)).is_false();
+            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(10));
+
+            // alive magotchis cannot be revived
+            assert_that!(execute_revive(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::MagotchiNotDead {});
+        }
+
+        #[test]
+        fn test_execute_revive_reaped() {
+            let (mut deps, env) = prepare_dead(Some(RevivalConfig {
+                cost_multiplier: 2,
+                max_revivals: Some(1),
+            }));
+            let _ = execute_reap(
+                &mut deps.as_mut(),
+                Some(vec!["magotchi1".to_string()]),
+                &env,
+                &mock_info("reaper", &[]),
+            )
+            .unwrap();
+
+            // once reaped, only the graveyard can revive
+            let info = mock_info(OWNER, &coins(20_000_000, "uluna"));
+            assert_that!(execute_revive(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::Unauthorized {});
+
+            let info = mock_info("graveyard", &coins(20_000_000, "uluna"));
+            assert_that!(execute_revive(&mut deps.as_mut(), &env, &info, "magotchi1")).is_ok();
+            assert_that!(graves().has(&deps.storage, "magotchi1".to_string())).is_false();
+
+            // the revival limit is reached
+            let mut env = env;
+            env.block.time = env.block.time.plus_days(11);
+            assert_that!(execute_revive(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::MaxRevivalsReached { max: 1 });
+        }
+    }
+
+    mod vacation {
+        use super::*;
+        use crate::state::VacationConfig;
+
+        #[test]
+        fn test_execute_start_vacation() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            let info = mock_info(OWNER, &coins(3_000, "uluna"));
+            assert_that!(execute_start_vacation(
+                &mut deps.as_mut(),
+                &env,
+                &info,
+                "magotchi1",
+                3
+            ))
+            .is_err()
+            .is_equal_to(ContractError::VacationDisabled {});
+
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.vacation = Some(VacationConfig {
+                        price_per_day: coins(1_000, "uluna"),
+                        max_days: 7,
+                        max_days_per_year: 30,
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+
+            // only the owner can buy a vacation
+            let other = mock_info("someone", &coins(3_000, "uluna"));
+            assert_that!(execute_start_vacation(
+                &mut deps.as_mut(),
+                &env,
+                &other,
+                "magotchi1",
+                3
+            ))
+            .is_err()
+            .is_equal_to(ContractError::Unauthorized {});
+
+            let underpaid = mock_info(OWNER, &coins(2_000, "uluna"));
+            assert_that!(execute_start_vacation(
+                &mut deps.as_mut(),
+                &env,
+                &underpaid,
+                "magotchi1",
+                3
+            ))
+            .is_err()
+            .is_equal_to(ContractError::InvalidPayment {
+                payed: coin(2_000, "uluna"),
+                expected: coin(3_000, "uluna"),
+            });
+
+            let res =
+                execute_start_vacation(&mut deps.as_mut(), &env, &info, "magotchi1", 3).unwrap();
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "start_vacation"),
+                attr("token_id", "magotchi1"),
+                attr("days", "3"),
+            ]);
+
+            // the magotchi would have died after one day, but is on vacation
+            let state = LIVE_STATES
+                .load(&deps.storage, "magotchi1".to_string())
+                .unwrap();
+            assert_that!(state.paused_until()).is_equal_to(Some(env.block.time.plus_days(3)));
+            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(4));
+        }
+    }
+
+    mod pause {
+        use super::*;
+
+        #[test]
+        fn test_execute_pause() {
+            let mut deps = prepare_cw721_base_state();
+            let mut env = mock_env();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            let death_time = LIVE_STATES
+                .load(&deps.storage, "magotchi1".to_string())
+                .unwrap()
+                .death_time();
+
+            // only the owner can pause
+            assert_that!(execute_pause(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("someone", &[])
+            ))
+            .is_err();
+            let owner = mock_info(MINTER, &[]);
+            let _ = execute_pause(&mut deps.as_mut(), &env, &owner).unwrap();
+
+            env.block.time = env.block.time.plus_days(3);
+            let info = mock_info("feeder", &coins(8_000_000, "uluna"));
+            assert_that!(execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1"))
+                .is_err()
+                .is_equal_to(ContractError::ContractPaused {});
+            assert_that!(execute_hatch(&mut deps.as_mut(), &env, "magotchi2"))
+                .is_err()
+                .is_equal_to(ContractError::ContractPaused {});
+            assert_that!(execute_reap(
+                &mut deps.as_mut(),
+                None,
+                &env,
+                &mock_info("reaper", &[])
+            ))
+            .is_err()
+            .is_equal_to(ContractError::ContractPaused {});
+
+            let res = execute_unpause(&mut deps.as_mut(), &env, &owner).unwrap();
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "unpause"),
+                attr("paused_seconds", (3 * 24 * 60 * 60).to_string()),
+            ]);
+            assert_that!(execute_unpause(&mut deps.as_mut(), &env, &owner))
+                .is_err()
+                .is_equal_to(ContractError::NotPaused {});
+
+            // the death time is shifted by the paused duration
+            let state = load_gotchi(&deps.storage, "magotchi1").unwrap();
+            assert_that!(state.death_time()).is_equal_to(death_time.plus_days(3));
+            assert_that!(execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1")).is_ok();
+        }
+    }
+
+    mod update_config {
+        use super::*;
+
+        #[test]
+        fn test_execute_update_config() {
+            let mut deps = prepare_cw721_base_state();
+            let partial_config = || PartialConfig {
+                max_unfed_days: Some(20),
+                ..Default::default()
+            };
+
+            // only the owner can update the config
+            assert_that!(execute_update_config(
+                &mut deps.as_mut(),
+                &mock_info(OWNER, &[]),
+                partial_config()
+            ))
+            .is_err()
+            .is_equal_to(ContractError::Ownership(
+                cw_ownable::OwnershipError::NotOwner,
+            ));
+            assert_that!(CONFIG.load(&deps.storage).unwrap().max_unfed_days).is_equal_to(10);
+
+            execute_update_config(
+                &mut deps.as_mut(),
+                &mock_info(MINTER, &[]),
+                partial_config(),
+            )
+            .unwrap();
+            assert_that!(CONFIG.load(&deps.storage).unwrap().max_unfed_days).is_equal_to(20);
+        }
+    }
+
+    mod breed {
+        use super::*;
+        use crate::state::{BreedingConfig, GeneticsConfig, TraitTable, WeightedTrait};
+
+        fn set_attributes(deps: &mut DepsMut, token_id: &str, attributes: Vec<(&str, &str)>) {
+            let contract = Cw721MetadataContract::default();
+            let mut token = contract.tokens.load(deps.storage, token_id).unwrap();
+            token.extension = Some(Metadata {
+                attributes: Some(
+                    attributes
+                        .into_iter()
+                        .map(|(trait_type, value)| Trait {
+                            display_type: None,
+                            trait_type: trait_type.to_string(),
+                            value: value.to_string(),
+                        })
+                        .collect(),
+                ),
+                ..Metadata::default()
+            });
+            contract
+                .tokens
+                .save(deps.storage, token_id, &token)
+                .unwrap();
+        }
+
+        #[test]
+        fn test_execute_breed() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            let info = mock_info(OWNER, &coins(5_000, "uluna"));
+
+            assert_that!(execute_breed(
+                &mut deps.as_mut(),
+                &env,
+                &info,
+                "magotchi1",
+                "magotchi2"
+            ))
+            .is_err()
+            .is_equal_to(ContractError::BreedingDisabled {});
+
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.breeding = Some(BreedingConfig {
+                        fee: coins(5_000, "uluna"),
+                        min_age_days: 0,
+                        cooldown_days: 1,
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+            set_attributes(
+                &mut deps.as_mut(),
+                "magotchi1",
+                vec![("generation", "1"), ("color", "red")],
+            );
+            set_attributes(
+                &mut deps.as_mut(),
+                "magotchi2",
+                vec![("color", "blue"), ("species", "newt")],
+            );
+
+            // unhatched parents cannot breed
+            assert_that!(execute_breed(
+                &mut deps.as_mut(),
+                &env,
+                &info,
+                "magotchi1",
+                "magotchi2"
+            ))
+            .is_err()
+            .is_equal_to(ContractError::MagotchiUnhatched {});
+
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi2").unwrap();
+
+            // only the owner or an approved address can breed
+            let other = mock_info("someone", &coins(5_000, "uluna"));
+            assert_that!(execute_breed(
+                &mut deps.as_mut(),
+                &env,
+                &other,
+                "magotchi1",
+                "magotchi2"
+            ))
+            .is_err();
+
+            let res =
+                execute_breed(&mut deps.as_mut(), &env, &info, "magotchi1", "magotchi2").unwrap();
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "breed"),
+                attr("parent_a", "magotchi1"),
+                attr("parent_b", "magotchi2"),
+                attr("token_id", "egg-0"),
+            ]);
+
+            let egg = Cw721MetadataContract::default()
+                .tokens
+                .load(&deps.storage, "egg-0")
+                .unwrap();
+            assert_that!(egg.owner).is_equal_to(Addr::unchecked(OWNER));
+            let attributes: Vec<(String, String)> = egg
+                .extension
+                .unwrap()
+                .attributes
+                .unwrap()
+                .into_iter()
+                .map(|t| (t.trait_type, t.value))
+                .collect();
+            assert_that!(attributes).is_equal_to(vec![
+                ("parent_a".to_string(), "magotchi1".to_string()),
+                ("parent_b".to_string(), "magotchi2".to_string()),
+                ("generation".to_string(), "2".to_string()),
+                ("color".to_string(), "blue".to_string()),
+                ("species".to_string(), "newt".to_string()),
+            ]);
+            let state = LIVE_STATES
+                .load(&deps.storage, "egg-0".to_string())
+                .unwrap();
+            assert_that!(state.is_hatched()).is_false();
+
+            // both parents are on cooldown
+            assert_that!(execute_breed(
+                &mut deps.as_mut(),
+                &env,
+                &info,
+                "magotchi2",
+                "magotchi3"
+            ))
+            .is_err()
+            .is_equal_to(ContractError::BreedingCooldown {});
+        }
+
+        #[test]
+        fn test_breed_inherits_genes() {
+            let mut deps = prepare_cw721_base_state();
+            let mut env = mock_env();
+            let option = |value: &str, hunger_multiplier| WeightedTrait {
+                value: value.to_string(),
+                weight: 1,
+                hunger_multiplier,
+            };
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.breeding = Some(BreedingConfig {
+                        fee: coins(5_000, "uluna"),
+                        min_age_days: 0,
+                        cooldown_days: 1,
+                    });
+                    config.genetics = Some(GeneticsConfig {
+                        traits: vec![
+                            TraitTable {
+                                trait_type: "color".to_string(),
+                                options: vec![option("red", Some(2000))],
+                            },
+                            TraitTable {
+                                trait_type: "species".to_string(),
+                                options: vec![option("newt", None), option("axolotl", None)],
+                            },
+                        ],
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi2").unwrap();
+            let info = mock_info(OWNER, &coins(5_000, "uluna"));
+            let _ =
+                execute_breed(&mut deps.as_mut(), &env, &info, "magotchi1", "magotchi2").unwrap();
+
+            let genome = load_gotchi(&deps.storage, "egg-0")
+                .unwrap()
+                .genome()
+                .cloned()
+                .unwrap();
+            assert_that!(genome.traits[0].value).is_equal_to("red".to_string());
+            assert_that!(genome.hunger_multiplier).is_equal_to(2000);
+            let species: Vec<String> = ["magotchi1", "magotchi2"]
+                .iter()
+                .map(|parent| {
+                    load_gotchi(&deps.storage, parent)
+                        .unwrap()
+                        .genome()
+                        .unwrap()
+                        .traits[1]
+                        .value
+                        .clone()
+                })
+                .collect();
+            assert_that!(species.contains(&genome.traits[1].value)).is_true();
+
+            // hatching keeps the inherited genes
+            env.block.height += 1;
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "egg-0").unwrap();
+            let state = load_gotchi(&deps.storage, "egg-0").unwrap();
+            assert_that!(state.genome()).is_equal_to(Some(&genome));
+        }
+    }
+
+    mod genetics {
+        use super::*;
+        use crate::state::{GeneticsConfig, TraitTable, WeightedTrait};
+
+        #[test]
+        fn test_execute_hatch_with_genetics() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.genetics = Some(GeneticsConfig {
+                        traits: vec![TraitTable {
+                            trait_type: "species".to_string(),
+                            options: vec![WeightedTrait {
+                                value: "newt".to_string(),
+                                weight: 1,
+                                hunger_multiplier: Some(2000),
+                            }],
+                        }],
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+
+            let state = LIVE_STATES
+                .load(&deps.storage, "magotchi1".to_string())
+                .unwrap();
+            let genome = state.genome().unwrap();
+            assert_that!(genome.hunger_multiplier).is_equal_to(2000);
+
+            let token = Cw721MetadataContract::default()
+                .tokens
+                .load(&deps.storage, "magotchi1")
+                .unwrap();
+            assert_that!(token.extension.unwrap().attributes)
+                .is_equal_to(Some(genome.traits.clone()));
+
+            // a hungry species only lasts half of the max_unfed_days
+            let info = mock_info("feeder", &coins(3_000_000, "uluna"));
+            let _ = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+            let state = LIVE_STATES
+                .load(&deps.storage, "magotchi1".to_string())
+                .unwrap();
+            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(5));
+
+            // hatching doesn't touch frozen metadata
+            Cw721MetadataContract::default()
+                .freeze_metadata(&mut deps.storage, &Addr::unchecked(MINTER))
+                .unwrap();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi2").unwrap();
+            let state = LIVE_STATES
+                .load(&deps.storage, "magotchi2".to_string())
+                .unwrap();
+            assert_that!(state.genome()).is_some();
+            let token = Cw721MetadataContract::default()
+                .tokens
+                .load(&deps.storage, "magotchi2")
+                .unwrap();
+            assert_that!(token.extension).is_none();
+        }
+    }
+
+    mod public_mint {
+        use super::*;
+        use crate::state::PublicMintConfig;
+
+        fn prepare_public_mint(
+            start_time: Option<Timestamp>,
+        ) -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+            let mut deps = prepare_cw721_base_state();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.public_mint = Some(PublicMintConfig {
+                        price: vec![coin(1_000, "uluna"), coin(5, "untrn")],
+                        max_supply: 2,
+                        per_address_limit: 1,
+                        start_time,
+                        end_time: None,
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+            deps
+        }
+
+        #[test]
+        fn test_execute_public_mint() {
+            let mut deps = prepare_public_mint(None);
+            let env = mock_env();
+
+            let info = mock_info("buyer", &coins(999, "uluna"));
+            assert_that!(execute_public_mint(&mut deps.as_mut(), &env, &info))
+                .is_err()
+                .is_equal_to(ContractError::InvalidPayment {
+                    payed: coin(999, "uluna"),
+                    expected: coin(1_000, "uluna"),
+                });
+
+            let info = mock_info("buyer", &coins(1_000, "uluna"));
+            let res = execute_public_mint(&mut deps.as_mut(), &env, &info).unwrap();
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "public_mint"),
+                attr("token_id", "token-0"),
+                attr("owner", "buyer"),
+            ]);
+            let token = Cw721MetadataContract::default()
+                .tokens
+                .load(&deps.storage, "token-0")
+                .unwrap();
+            assert_that!(token.owner).is_equal_to(Addr::unchecked("buyer"));
+            assert_that!(LIVE_STATES.has(&deps.storage, "token-0".to_string())).is_true();
+
+            assert_that!(execute_public_mint(&mut deps.as_mut(), &env, &info))
+                .is_err()
+                .is_equal_to(ContractError::MintLimitReached { limit: 1 });
+
+            let info = mock_info("other_buyer", &coins(5, "untrn"));
+            let res = execute_public_mint(&mut deps.as_mut(), &env, &info).unwrap();
+            assert_that!(res.attributes[1]).is_equal_to(attr("token_id", "token-1"));
+
+            // burning a public mint doesn't free up supply
+            crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("buyer", &[]),
+                ExecuteMsg::Burn {
+                    token_id: "token-0".to_string(),
+                },
+            )
+            .unwrap();
+            let info = mock_info("late_buyer", &coins(1_000, "uluna"));
+            assert_that!(execute_public_mint(&mut deps.as_mut(), &env, &info))
+                .is_err()
+                .is_equal_to(ContractError::SoldOut {});
+        }
+
+        #[test]
+        fn test_execute_public_mint_disabled() {
+            let mut deps = prepare_cw721_base_state();
+
+            let info = mock_info("buyer", &coins(1_000, "uluna"));
+            assert_that!(execute_public_mint(&mut deps.as_mut(), &mock_env(), &info))
+                .is_err()
+                .is_equal_to(ContractError::MintDisabled {});
+        }
+
+        #[test]
+        fn test_execute_public_mint_not_started() {
+            let env = mock_env();
+            let mut deps = prepare_public_mint(Some(env.block.time.plus_seconds(1)));
+
+            let info = mock_info("buyer", &coins(1_000, "uluna"));
+            assert_that!(execute_public_mint(&mut deps.as_mut(), &env, &info))
+                .is_err()
+                .is_equal_to(ContractError::MintNotStarted {});
+        }
+    }
+
+    mod presale {
+        use super::*;
+        use crate::merkle::{hash_pair, leaf_hash};
+        use crate::state::PresaleConfig;
+
+        #[test]
+        fn test_execute_presale_mint() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            let root = hash_pair(&leaf_hash("alice"), &leaf_hash("bob"));
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.presale = Some(PresaleConfig {
+                        merkle_root: hex::encode(root),
+                        price: coins(500, "uluna"),
+                        max_supply: 2,
+                        per_address_limit: 1,
+                        start_time: None,
+                        end_time: Some(env.block.time.plus_days(1)),
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+            let alice_proof = vec![hex::encode(leaf_hash("bob"))];
+
+            // not eligible
+            let info = mock_info("carol", &coins(500, "uluna"));
+            assert_that!(execute_presale_mint(
+                &mut deps.as_mut(),
+                &env,
+                &info,
+                &alice_proof
+            ))
+            .is_err()
+            .is_equal_to(ContractError::InvalidMerkleProof {});
+
+            let info = mock_info("alice", &coins(500, "uluna"));
+            let res = execute_presale_mint(&mut deps.as_mut(), &env, &info, &alice_proof).unwrap();
+            assert_that!(res.attributes).is_equal_to(vec![
+                attr("action", "presale_mint"),
+                attr("token_id", "token-0"),
+                attr("owner", "alice"),
+            ]);
+
+            assert_that!(execute_presale_mint(
+                &mut deps.as_mut(),
+                &env,
+                &info,
+                &alice_proof
+            ))
+            .is_err()
+            .is_equal_to(ContractError::MintLimitReached { limit: 1 });
+
+            // the presale is over
+            let mut env = env;
+            env.block.time = env.block.time.plus_days(1);
+            let info = mock_info("bob", &coins(500, "uluna"));
+            let bob_proof = vec![hex::encode(leaf_hash("alice"))];
+            assert_that!(execute_presale_mint(
+                &mut deps.as_mut(),
+                &env,
+                &info,
+                &bob_proof
+            ))
+            .is_err()
+            .is_equal_to(ContractError::MintEnded {});
+        }
+
+        #[test]
+        fn test_presale_max_supply() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            let root = hash_pair(&leaf_hash("alice"), &leaf_hash("bob"));
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.presale = Some(PresaleConfig {
+                        merkle_root: hex::encode(root),
+                        price: coins(500, "uluna"),
+                        max_supply: 1,
+                        per_address_limit: 1,
+                        start_time: None,
+                        end_time: None,
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+
+            let info = mock_info("alice", &coins(500, "uluna"));
+            let alice_proof = vec![hex::encode(leaf_hash("bob"))];
+            execute_presale_mint(&mut deps.as_mut(), &env, &info, &alice_proof).unwrap();
+
+            let info = mock_info("bob", &coins(500, "uluna"));
+            let bob_proof = vec![hex::encode(leaf_hash("alice"))];
+            assert_that!(execute_presale_mint(
+                &mut deps.as_mut(),
+                &env,
+                &info,
+                &bob_proof
+            ))
+            .is_err()
+            .is_equal_to(ContractError::SoldOut {});
+        }
+    }
+
+    mod treasury {
+        use super::*;
+        use crate::msg::{AccruedResponse, TreasuryResponse};
+        use crate::query::query_treasury;
+        use crate::state::{RevenueSplit, TreasuryConfig};
+
+        #[test]
+        fn test_treasury() {
+            let mut deps = prepare_cw721_base_state();
+            let mut env = mock_env();
+            let config = CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.treasury = Some(TreasuryConfig {
+                        splits: vec![
+                            RevenueSplit {
+                                recipient: Recipient::Address {
+                                    address: Addr::unchecked("team"),
+                                },
+                                share: 700,
+                            },
+                            RevenueSplit {
+                                recipient: Recipient::Reapers {},
+                                share: 300,
+                            },
+                        ],
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            let info = mock_info("feeder", &coins(8_000_000, "uluna"));
+            let _ = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+
+            assert_that!(query_treasury(deps.as_ref()).unwrap()).is_equal_to(TreasuryResponse {
+                revenue: coins(8_000_000, "uluna"),
+                accrued: vec![
+                    AccruedResponse {
+                        recipient: "reapers".to_string(),
+                        amounts: coins(2_400_000, "uluna"),
+                    },
+                    AccruedResponse {
+                        recipient: "team".to_string(),
+                        amounts: coins(5_600_000, "uluna"),
+                    },
+                ],
+            });
+
+            // the address recipients are paid out on withdraw
+            let res = execute_withdraw_treasury(&mut deps.as_mut(), &config, "uluna").unwrap();
+            assert_that!(res.messages[0].msg).is_equal_to(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "team".to_string(),
+                amount: coins(5_600_000, "uluna"),
+            }));
+            assert_that!(query_treasury(deps.as_ref()).unwrap().accrued).has_length(1);
+
+            // the reaper rewards go to the next reaper
+            env.block.time = env.block.time.plus_days(11);
+            let res =
+                execute_reap(&mut deps.as_mut(), None, &env, &mock_info("reaper", &[])).unwrap();
+            assert_that!(res.messages[0].msg).is_equal_to(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "reaper".to_string(),
+                amount: coins(2_400_000, "uluna"),
+            }));
+            assert_that!(query_treasury(deps.as_ref()).unwrap().accrued).is_empty();
+        }
+
+        #[test]
+        fn test_removed_recipients_are_paid_out() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            let treasury = |address: &str| TreasuryConfig {
+                splits: vec![RevenueSplit {
+                    recipient: Recipient::Address {
+                        address: Addr::unchecked(address),
+                    },
+                    share: 1000,
+                }],
+            };
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.treasury = Some(treasury("team"));
+                    Ok(config)
+                })
+                .unwrap();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            let info = mock_info("feeder", &coins(8_000_000, "uluna"));
+            let _ = execute_feed(&mut deps.as_mut(), &env, &info, "magotchi1").unwrap();
+
+            // the team is replaced, but keeps the revenue it accrued until then
+            let config = CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.treasury = Some(treasury("dao"));
+                    Ok(config)
+                })
+                .unwrap();
+            let res = execute_withdraw_treasury(&mut deps.as_mut(), &config, "uluna").unwrap();
+            assert_that!(res.messages).has_length(1);
+            assert_that!(res.messages[0].msg).is_equal_to(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "team".to_string(),
+                amount: coins(8_000_000, "uluna"),
+            }));
+            assert_that!(query_treasury(deps.as_ref()).unwrap().accrued).is_empty();
+        }
+    }
+
+    mod royalties {
+        use super::*;
+        use crate::query::{query_check_royalties, query_royalty_info};
+        use cw2981_royalties::{CheckRoyaltiesResponse, RoyaltiesInfoResponse};
+
+        fn royalties(address: &str, percentage: u64) -> RoyaltyConfig {
+            RoyaltyConfig {
+                payment_address: Addr::unchecked(address),
+                percentage,
+            }
+        }
+
+        #[test]
+        fn test_royalty_info() {
+            let mut deps = prepare_cw721_base_state();
+
+            // no royalties by default
+            assert_that!(query_check_royalties(deps.as_ref()).unwrap()).is_equal_to(
+                CheckRoyaltiesResponse {
+                    royalty_payments: false,
+                },
+            );
+            assert_that!(query_royalty_info(
+                deps.as_ref(),
+                "magotchi1".to_string(),
+                Uint128::new(1000)
+            )
+            .unwrap())
+            .is_equal_to(RoyaltiesInfoResponse {
+                address: "".to_string(),
+                royalty_amount: Uint128::zero(),
+            });
+
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.royalties = Some(royalties("artist", 5));
+                    Ok(config)
+                })
+                .unwrap();
+            assert_that!(
+                query_check_royalties(deps.as_ref())
+                    .unwrap()
+                    .royalty_payments
+            )
+            .is_true();
+
+            // only the contract owner can override the royalties of a token
+            let err = execute_set_token_royalties(
+                &mut deps.as_mut(),
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                Some(royalties("owner", 10)),
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Ownership(
+                cw_ownable::OwnershipError::NotOwner,
+            ));
+
+            let err = execute_set_token_royalties(
+                &mut deps.as_mut(),
+                &mock_info(MINTER, &[]),
+                "magotchi1",
+                Some(royalties("collaborator", 101)),
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::InvalidConfig {});
+
+            execute_set_token_royalties(
+                &mut deps.as_mut(),
+                &mock_info(MINTER, &[]),
+                "magotchi1",
+                Some(royalties("collaborator", 10)),
+            )
+            .unwrap();
+
+            assert_that!(query_royalty_info(
+                deps.as_ref(),
+                "magotchi1".to_string(),
+                Uint128::new(1000)
+            )
+            .unwrap())
+            .is_equal_to(RoyaltiesInfoResponse {
+                address: "collaborator".to_string(),
+                royalty_amount: Uint128::new(100),
+            });
+            assert_that!(query_royalty_info(
+                deps.as_ref(),
+                "magotchi2".to_string(),
+                Uint128::new(1000)
+            )
+            .unwrap())
+            .is_equal_to(RoyaltiesInfoResponse {
+                address: "artist".to_string(),
+                royalty_amount: Uint128::new(50),
+            });
+
+            // removing the override falls back to the config
+            execute_set_token_royalties(
+                &mut deps.as_mut(),
+                &mock_info(MINTER, &[]),
+                "magotchi1",
+                None,
+            )
+            .unwrap();
+            assert_that!(
+                query_royalty_info(deps.as_ref(), "magotchi1".to_string(), Uint128::new(1000))
+                    .unwrap()
+                    .address
+            )
+            .is_equal_to("artist".to_string());
+        }
+    }
+
+    mod reveal {
+        use super::*;
+        use crate::query::query_nft_info;
+        use crate::state::RevealConfig;
+
+        fn metadata(name: &str) -> Metadata {
+            Metadata {
+                name: Some(name.to_string()),
+                ..Metadata::default()
+            }
+        }
+
+        #[test]
+        fn test_reveal() {
+            let mut deps = prepare_cw721_base_state();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.reveal = Some(RevealConfig {
+                        placeholder: metadata("mystery egg"),
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+
+            for token_id in ["egg1", "egg2"] {
+                mint_as_minter(&mut deps.as_mut(), token_id, &Addr::unchecked(OWNER), None)
+                    .unwrap();
+            }
+            // minted with metadata, so no mystery box
+            mint_as_minter(
+                &mut deps.as_mut(),
+                "egg3",
+                &Addr::unchecked(OWNER),
+                Some(metadata("egg3")),
+            )
+            .unwrap();
+
+            assert_that!(
+                query_nft_info(deps.as_ref(), "egg1".to_string())
+                    .unwrap()
+                    .extension
+            )
+            .is_equal_to(Some(metadata("mystery egg")));
+            assert_that!(
+                query_nft_info(deps.as_ref(), "egg3".to_string())
+                    .unwrap()
+                    .extension
+            )
+            .is_equal_to(Some(metadata("egg3")));
+
+            let batch = RevealBatch {
+                token_ids: vec!["egg1".to_string(), "egg2".to_string()],
+                metadata: vec![metadata("newt"), metadata("axolotl")],
+            };
+            let commitment = batch.commitment().unwrap();
+
+            // only the contract owner can commit
+            let err =
+                execute_commit_reveal(&mut deps.as_mut(), &mock_info(OWNER, &[]), &commitment)
+                    .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Ownership(
+                cw_ownable::OwnershipError::NotOwner,
+            ));
+            let err = execute_commit_reveal(&mut deps.as_mut(), &mock_info(MINTER, &[]), "hash")
+                .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::InvalidCommitment {});
+            execute_commit_reveal(&mut deps.as_mut(), &mock_info(MINTER, &[]), &commitment)
+                .unwrap();
+
+            // metadata that doesn't match the commitment
+            let err = execute_reveal(
+                &mut deps.as_mut(),
+                batch.token_ids.clone(),
+                vec![metadata("newt"), metadata("dragon")],
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::InvalidReveal {});
+
+            execute_reveal(
+                &mut deps.as_mut(),
+                batch.token_ids.clone(),
+                batch.metadata.clone(),
+            )
+            .unwrap();
+            assert_that!(
+                query_nft_info(deps.as_ref(), "egg1".to_string())
+                    .unwrap()
+                    .extension
+            )
+            .is_equal_to(Some(metadata("newt")));
+            assert_that!(
+                query_nft_info(deps.as_ref(), "egg2".to_string())
+                    .unwrap()
+                    .extension
+            )
+            .is_equal_to(Some(metadata("axolotl")));
+
+            // a commitment can only be revealed once
+            let err =
+                execute_reveal(&mut deps.as_mut(), batch.token_ids, batch.metadata).unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::InvalidReveal {});
+        }
+    }
+
+    mod transfer_mode {
+        use super::*;
+
+        fn set_transfer_mode(deps: &mut DepsMut, mode: TransferMode) {
+            Cw721MetadataContract::default()
+                .set_transfer_mode(deps.storage, &Addr::unchecked(MINTER), mode)
+                .unwrap();
+        }
+
+        #[test]
+        fn test_assert_transferable() {
+            let mut deps = prepare_cw721_base_state();
+            let mut env = mock_env();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            let tokens = |token_id: &str| vec![token_id.to_string()];
+            let non_transferable =
+                || ContractError::Cw721(cw721_base::ContractError::NonTransferable {});
+
+            // everything is transferable by default
+            assert_that!(assert_transferable(
+                &deps.storage,
+                &env.block,
+                &tokens("magotchi1")
+            ))
+            .is_ok();
+
+            set_transfer_mode(&mut deps.as_mut(), TransferMode::WhileUnhatched);
+            assert_that!(assert_transferable(
+                &deps.storage,
+                &env.block,
+                &tokens("magotchi1")
+            ))
+            .is_err()
+            .is_equal_to(non_transferable());
+            assert_that!(assert_transferable(
+                &deps.storage,
+                &env.block,
+                &tokens("magotchi2")
+            ))
+            .is_ok();
+
+            set_transfer_mode(&mut deps.as_mut(), TransferMode::WhileAlive);
+            assert_that!(assert_transferable(
+                &deps.storage,
+                &env.block,
+                &tokens("magotchi1")
+            ))
+            .is_ok();
+            env.block.time = env.block.time.plus_days(11);
+            assert_that!(assert_transferable(
+                &deps.storage,
+                &env.block,
+                &["magotchi2".to_string(), "magotchi1".to_string()]
+            ))
+            .is_err()
+            .is_equal_to(ContractError::DeadNotTransferable {
+                token_id: "magotchi1".to_string(),
+            });
+
+            // reaped magotchis can be transferred again, unless only living ones can
+            let _ = execute_reap(
+                &mut deps.as_mut(),
+                Some(tokens("magotchi1")),
+                &env,
+                &mock_info("reaper", &[]),
+            )
+            .unwrap();
+            assert_that!(assert_transferable(
+                &deps.storage,
+                &env.block,
+                &tokens("magotchi1")
+            ))
+            .is_err()
+            .is_equal_to(non_transferable());
+            set_transfer_mode(&mut deps.as_mut(), TransferMode::Transferable);
+            assert_that!(assert_transferable(
+                &deps.storage,
+                &env.block,
+                &tokens("magotchi1")
+            ))
+            .is_ok();
+        }
+
         #[test]
-        fn test_execute_feed() {
-            let mut deps = prepare();
+        fn test_approvals_follow_transfer_mode() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            set_transfer_mode(&mut deps.as_mut(), TransferMode::WhileUnhatched);
+            let approve = |token_id: &str| ExecuteMsg::Approve {
+                spender: "operator".to_string(),
+                token_id: token_id.to_string(),
+                expires: None,
+            };
+            let approve_all = ExecuteMsg::ApproveAll {
+                operator: "operator".to_string(),
+                expires: None,
+            };
 
-            // Mock environment and message info
+            let err = crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(OWNER, &[]),
+                approve("magotchi1"),
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Cw721(
+                cw721_base::ContractError::NonTransferable {},
+            ));
+            crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(OWNER, &[]),
+                approve("magotchi2"),
+            )
+            .unwrap();
+
+            // operators can be approved, but still can't move the hatched magotchi
+            crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(OWNER, &[]),
+                approve_all.clone(),
+            )
+            .unwrap();
+            let err = crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("operator", &[]),
+                ExecuteMsg::TransferNft {
+                    recipient: "operator".to_string(),
+                    token_id: "magotchi1".to_string(),
+                },
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Cw721(
+                cw721_base::ContractError::NonTransferable {},
+            ));
+
+            set_transfer_mode(&mut deps.as_mut(), TransferMode::Soulbound);
+            let err = crate::entry::execute(deps.as_mut(), env, mock_info(OWNER, &[]), approve_all)
+                .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Cw721(
+                cw721_base::ContractError::NonTransferable {},
+            ));
+        }
+
+        #[test]
+        fn test_hunger_tax() {
+            let mut deps = prepare_cw721_base_state();
             let env = mock_env();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.hunger_tax = true;
+                    Ok(config)
+                })
+                .unwrap();
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            let transfer_msg = ExecuteMsg::TransferNft {
+                recipient: "buyer".to_string(),
+                token_id: "magotchi1".to_string(),
+            };
 
-            // Hatch the magotchi first
+            // unhatched magotchis are never hungry
+            assert_that!(assert_transferable(
+                &deps.storage,
+                &env.block,
+                &["magotchi2".to_string()]
+            ))
+            .is_ok();
+
+            let err = crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(OWNER, &[]),
+                transfer_msg.clone(),
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::HungerTaxDue {
+                token_id: "magotchi1".to_string(),
+            });
+
+            // paying the feeding cost with the transfer feeds the magotchi first
+            crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(OWNER, &coins(8_000_000, "uluna")),
+                transfer_msg,
+            )
+            .unwrap();
+            let owner = Cw721MetadataContract::default()
+                .tokens
+                .load(&deps.storage, "magotchi1")
+                .unwrap()
+                .owner;
+            assert_that!(owner).is_equal_to(Addr::unchecked("buyer"));
+
+            // a fed magotchi owes no tax, so the funds are rejected
+            let err = crate::entry::execute(
+                deps.as_mut(),
+                env,
+                mock_info("buyer", &coins(8_000_000, "uluna")),
+                ExecuteMsg::TransferNft {
+                    recipient: OWNER.to_string(),
+                    token_id: "magotchi1".to_string(),
+                },
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::NoHungerTaxDue {
+                token_id: "magotchi1".to_string(),
+            });
+
+            // the graveyard can move reaped magotchis without paying their tax
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_days(11);
+            execute_reap(
+                &mut deps.as_mut(),
+                Some(vec!["magotchi1".to_string()]),
+                &env,
+                &mock_info("reaper", &[]),
+            )
+            .unwrap();
+            assert_that!(assert_transferable(
+                &deps.storage,
+                &env.block,
+                &["magotchi1".to_string()]
+            ))
+            .is_ok();
+        }
+    }
+
+    mod marketplace {
+        use super::*;
+        use crate::query::{query_listings, query_listings_by_price, query_listings_by_seller};
+        use crate::state::{ListingStatus, MarketplaceConfig};
+
+        fn enable_marketplace(deps: &mut DepsMut) {
+            CONFIG
+                .update(deps.storage, |mut config| -> StdResult<_> {
+                    config.marketplace = Some(MarketplaceConfig {
+                        protocol_fee: 50,
+                        ..Default::default()
+                    });
+                    config.royalties = Some(RoyaltyConfig {
+                        payment_address: Addr::unchecked("artist"),
+                        percentage: 10,
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+        }
+
+        fn list(deps: &mut DepsMut, env: &Env, token_id: &str, price: Coin) {
+            let _ = execute_list(deps, env, &mock_info(OWNER, &[]), token_id, price, None).unwrap();
+        }
+
+        fn listed_tokens(deps: Deps, env: &Env, status: Option<ListingStatus>) -> Vec<String> {
+            query_listings(deps, env.clone(), status, None, None)
+                .unwrap()
+                .listings
+                .into_iter()
+                .map(|listing| listing.token_id)
+                .collect()
+        }
+
+        #[test]
+        fn test_list_and_buy() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            let price = coin(1000, "uluna");
+
+            let err = execute_list(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                price.clone(),
+                None,
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::MarketplaceDisabled {});
+
+            enable_marketplace(&mut deps.as_mut());
+            let err = execute_list(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("thief", &[]),
+                "magotchi1",
+                price.clone(),
+                None,
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Unauthorized {});
+            let err = execute_list(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                coin(0, "uluna"),
+                None,
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::InvalidPrice {});
+
+            list(&mut deps.as_mut(), &env, "magotchi1", price.clone());
+
+            // the exact price must be paid
+            let err = execute_buy(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("buyer", &coins(999, "uluna")),
+                "magotchi1",
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::InvalidPayment {
+                payed: coin(999, "uluna"),
+                expected: price.clone(),
+            });
+
+            let res = execute_buy(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("buyer", &[price.clone()]),
+                "magotchi1",
+            )
+            .unwrap();
+            // 10% royalties, 5% protocol fee, which stays in the contract
+            assert_that!(res
+                .messages
+                .iter()
+                .map(|msg| msg.msg.clone())
+                .collect::<Vec<_>>())
+            .is_equal_to(vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "artist".to_string(),
+                    amount: coins(100, "uluna"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: OWNER.to_string(),
+                    amount: coins(850, "uluna"),
+                }),
+            ]);
+
+            let token = Cw721MetadataContract::default()
+                .tokens
+                .load(&deps.storage, "magotchi1")
+                .unwrap();
+            assert_that!(token.owner).is_equal_to(Addr::unchecked("buyer"));
+            assert_that!(token.approvals).is_empty();
+            let err = execute_buy(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("buyer", &[price]),
+                "magotchi1",
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::NotListed {
+                token_id: "magotchi1".to_string(),
+            });
+        }
+
+        #[test]
+        fn test_royalties_are_capped_by_the_protocol_fee() {
+            let mut deps = prepare_cw721_base_state();
+            enable_marketplace(&mut deps.as_mut());
+            let config = CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.marketplace = Some(MarketplaceConfig {
+                        protocol_fee: 950,
+                        ..Default::default()
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+
+            // 10% royalties and a 95% fee, so the royalties get what is left
+            let payouts = pay_sale(
+                &mut deps.storage,
+                &config,
+                config.marketplace.as_ref().unwrap(),
+                "magotchi1",
+                &Addr::unchecked(OWNER),
+                &coin(1000, "uluna"),
+            )
+            .unwrap();
+            assert_that!(payouts).is_equal_to(vec![BankMsg::Send {
+                to_address: "artist".to_string(),
+                amount: coins(50, "uluna"),
+            }]);
+        }
+
+        #[test]
+        fn test_delist() {
+            let mut deps = prepare_cw721_base_state();
+            let mut env = mock_env();
+            enable_marketplace(&mut deps.as_mut());
+            let _ = execute_list(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                coin(1000, "uluna"),
+                Some(Expiration::AtHeight(env.block.height + 10)),
+            )
+            .unwrap();
+
+            let err = execute_delist(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("thief", &[]),
+                "magotchi1",
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Unauthorized {});
+
+            // expired listings can't be bought, only delisted
+            env.block.height += 10;
+            assert_that!(listed_tokens(
+                deps.as_ref(),
+                &env,
+                Some(ListingStatus::Expired)
+            ))
+            .is_equal_to(vec!["magotchi1".to_string()]);
+            let err = execute_buy(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("buyer", &coins(1000, "uluna")),
+                "magotchi1",
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::ListingExpired {
+                token_id: "magotchi1".to_string(),
+            });
+
+            let _ = execute_delist(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+            )
+            .unwrap();
+            assert_that!(listed_tokens(deps.as_ref(), &env, None)).is_empty();
+            let token = Cw721MetadataContract::default()
+                .tokens
+                .load(&deps.storage, "magotchi1")
+                .unwrap();
+            assert_that!(token.approvals).is_empty();
+        }
+
+        #[test]
+        fn test_auto_delist() {
+            let mut deps = prepare_cw721_base_state();
+            let mut env = mock_env();
+            enable_marketplace(&mut deps.as_mut());
             let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            for token_id in TEST_TOKENS {
+                list(&mut deps.as_mut(), &env, token_id, coin(1000, "uluna"));
+            }
 
-            // Execute feed. After hatch, the magotchi is unfed for 9 days.
-            let info = mock_info("feeder", &coins(8_000_000, "uluna"));
-            let res = execute_feed(&mut deps.as_mut(), &env, "magotchi1", &info.funds).unwrap();
+            // transferring a magotchi removes its listing
+            crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(OWNER, &[]),
+                ExecuteMsg::TransferNft {
+                    recipient: "friend".to_string(),
+                    token_id: "magotchi2".to_string(),
+                },
+            )
+            .unwrap();
+            assert_that!(listed_tokens(deps.as_ref(), &env, None))
+                .is_equal_to(vec!["magotchi1".to_string(), "magotchi3".to_string()]);
 
-            // Verify the response
-            assert_that!(res.attributes)
-                .is_equal_to(vec![attr("action", "feed"), attr("token_id", "magotchi1")]);
+            // dead magotchis can't be bought, reaping them removes their listing
+            env.block.time = env.block.time.plus_days(11);
+            assert_that!(listed_tokens(
+                deps.as_ref(),
+                &env,
+                Some(ListingStatus::Dead)
+            ))
+            .is_equal_to(vec!["magotchi1".to_string()]);
 
-            // Verify the state changes
-            let state = LIVE_STATES
-                .load(&deps.storage, "magotchi1".to_string())
+            // the limit bounds the listings looked at, not the ones that match
+            let page = query_listings(
+                deps.as_ref(),
+                env.clone(),
+                Some(ListingStatus::Dead),
+                Some("magotchi1".to_string()),
+                Some(1),
+            )
+            .unwrap();
+            assert_that!(page.listings).is_empty();
+            assert_that!(page.last_token_id).is_equal_to(Some("magotchi3".to_string()));
+
+            let err = execute_buy(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("buyer", &coins(1000, "uluna")),
+                "magotchi1",
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::DeadNotTransferable {
+                token_id: "magotchi1".to_string(),
+            });
+
+            let _ = execute_reap(
+                &mut deps.as_mut(),
+                Some(vec!["magotchi1".to_string()]),
+                &env,
+                &mock_info("reaper", &[]),
+            )
+            .unwrap();
+            assert_that!(listed_tokens(deps.as_ref(), &env, None))
+                .is_equal_to(vec!["magotchi3".to_string()]);
+        }
+
+        #[test]
+        fn test_listing_queries() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            enable_marketplace(&mut deps.as_mut());
+            list(&mut deps.as_mut(), &env, "magotchi1", coin(3000, "uluna"));
+            list(&mut deps.as_mut(), &env, "magotchi2", coin(1000, "uluna"));
+            list(&mut deps.as_mut(), &env, "magotchi3", coin(2000, "untrn"));
+
+            let by_price = |start_after: Option<String>| -> Vec<String> {
+                query_listings_by_price(
+                    deps.as_ref(),
+                    env.clone(),
+                    "uluna".to_string(),
+                    start_after,
+                    None,
+                )
+                .unwrap()
+                .listings
+                .into_iter()
+                .map(|listing| listing.token_id)
+                .collect()
+            };
+            assert_that!(by_price(None))
+                .is_equal_to(vec!["magotchi2".to_string(), "magotchi1".to_string()]);
+            assert_that!(by_price(Some("magotchi2".to_string())))
+                .is_equal_to(vec!["magotchi1".to_string()]);
+
+            let listings = query_listings_by_seller(
+                deps.as_ref(),
+                env.clone(),
+                OWNER.to_string(),
+                Some("magotchi1".to_string()),
+                Some(1),
+            )
+            .unwrap()
+            .listings;
+            assert_that!(listings).has_length(1);
+            assert_that!(listings[0].token_id).is_equal_to("magotchi2".to_string());
+            assert_that!(listings[0].status).is_equal_to(ListingStatus::Active);
+        }
+    }
+
+    mod auction {
+        use super::*;
+        use crate::query::query_auction;
+        use crate::state::{AuctionDeathRule, MarketplaceConfig};
+
+        const CONTRACT: &str = "cosmos2contract";
+
+        fn enable_auctions(deps: &mut DepsMut, on_death: AuctionDeathRule) {
+            CONFIG
+                .update(deps.storage, |mut config| -> StdResult<_> {
+                    config.marketplace = Some(MarketplaceConfig {
+                        protocol_fee: 50,
+                        anti_snipe_seconds: 600,
+                        on_death,
+                    });
+                    config.royalties = Some(RoyaltyConfig {
+                        payment_address: Addr::unchecked("artist"),
+                        percentage: 10,
+                    });
+                    Ok(config)
+                })
                 .unwrap();
-            assert_that!(state.is_hatched()).is_true();
-            assert_that!(state.is_dead(&env.block)).is_false();
-            assert_that!(state.hatched_at())
-                .is_some()
-                .is_equal_to(env.block.time);
+        }
+
+        fn start_auction(deps: &mut DepsMut, env: &Env, token_id: &str) {
+            let _ = execute_start_auction(
+                deps,
+                env,
+                &mock_info(OWNER, &[]),
+                token_id,
+                coin(1000, "uluna"),
+                Uint128::new(100),
+                3600,
+            )
+            .unwrap();
+        }
+
+        fn bid(deps: &mut DepsMut, env: &Env, bidder: &str, amount: u128) -> CResult<Response> {
+            execute_bid(
+                deps,
+                env,
+                &mock_info(bidder, &coins(amount, "uluna")),
+                "magotchi1",
+            )
+        }
+
+        fn owner_of(deps: Deps, token_id: &str) -> Addr {
+            Cw721MetadataContract::default()
+                .tokens
+                .load(deps.storage, token_id)
+                .unwrap()
+                .owner
+        }
+
+        fn reserved(deps: Deps) -> Uint128 {
+            Cw721MetadataContract::default()
+                .reserved(deps.storage, "uluna")
+                .unwrap()
+        }
+
+        fn bank_messages(res: &Response) -> Vec<CosmosMsg> {
+            res.messages.iter().map(|msg| msg.msg.clone()).collect()
+        }
+
+        #[test]
+        fn test_auction() {
+            let mut deps = prepare_cw721_base_state();
+            let mut env = mock_env();
+            enable_auctions(&mut deps.as_mut(), AuctionDeathRule::Cancel);
+            start_auction(&mut deps.as_mut(), &env, "magotchi1");
+            assert_that!(owner_of(deps.as_ref(), "magotchi1"))
+                .is_equal_to(Addr::unchecked(CONTRACT));
+
+            assert_that!(bid(&mut deps.as_mut(), &env, "bidder1", 999))
+                .is_err()
+                .is_equal_to(ContractError::BidTooLow {
+                    min_bid: coin(1000, "uluna"),
+                });
+            let res = bid(&mut deps.as_mut(), &env, "bidder1", 1000).unwrap();
+            assert_that!(res.messages).is_empty();
+            assert_that!(reserved(deps.as_ref())).is_equal_to(Uint128::new(1000));
+            assert_that!(bid(&mut deps.as_mut(), &env, "bidder2", 1050))
+                .is_err()
+                .is_equal_to(ContractError::BidTooLow {
+                    min_bid: coin(1100, "uluna"),
+                });
+
+            // the outbid bidder is refunded
+            let res = bid(&mut deps.as_mut(), &env, "bidder2", 1100).unwrap();
+            assert_that!(bank_messages(&res)).is_equal_to(vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bidder1".to_string(),
+                amount: coins(1000, "uluna"),
+            })]);
+            assert_that!(reserved(deps.as_ref())).is_equal_to(Uint128::new(1100));
+
+            // a bid right before the end extends the auction
+            env.block.time = env.block.time.plus_seconds(3540);
+            let _ = bid(&mut deps.as_mut(), &env, "bidder1", 1200).unwrap();
+            env.block.time = env.block.time.plus_seconds(60);
+            let err = execute_settle_auction(&mut deps.as_mut(), &env, "magotchi1").unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::AuctionNotEnded {
+                token_id: "magotchi1".to_string(),
+            });
+
+            env.block.time = env.block.time.plus_seconds(540);
+            assert_that!(bid(&mut deps.as_mut(), &env, "bidder2", 1300))
+                .is_err()
+                .is_equal_to(ContractError::AuctionEnded {
+                    token_id: "magotchi1".to_string(),
+                });
+            let res = execute_settle_auction(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            // 10% royalties, 5% protocol fee, which stays in the contract
+            assert_that!(bank_messages(&res)).is_equal_to(vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "artist".to_string(),
+                    amount: coins(120, "uluna"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: OWNER.to_string(),
+                    amount: coins(1020, "uluna"),
+                }),
+            ]);
+            assert_that!(owner_of(deps.as_ref(), "magotchi1"))
+                .is_equal_to(Addr::unchecked("bidder1"));
+            assert_that!(AUCTIONS.has(&deps.storage, "magotchi1")).is_false();
+            assert_that!(reserved(deps.as_ref())).is_equal_to(Uint128::zero());
+        }
+
+        #[test]
+        fn test_cancel_auction() {
+            let mut deps = prepare_cw721_base_state();
+            let env = mock_env();
+            enable_auctions(&mut deps.as_mut(), AuctionDeathRule::Cancel);
+            start_auction(&mut deps.as_mut(), &env, "magotchi1");
+
+            let err =
+                execute_cancel_auction(&mut deps.as_mut(), &mock_info("bidder1", &[]), "magotchi1")
+                    .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Unauthorized {});
+
+            let _ = execute_cancel_auction(&mut deps.as_mut(), &mock_info(OWNER, &[]), "magotchi1")
+                .unwrap();
+            assert_that!(owner_of(deps.as_ref(), "magotchi1")).is_equal_to(Addr::unchecked(OWNER));
+
+            // auctions with bids can only be settled
+            start_auction(&mut deps.as_mut(), &env, "magotchi1");
+            let _ = bid(&mut deps.as_mut(), &env, "bidder1", 1000).unwrap();
+            let err =
+                execute_cancel_auction(&mut deps.as_mut(), &mock_info(OWNER, &[]), "magotchi1")
+                    .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::AuctionHasBids {
+                token_id: "magotchi1".to_string(),
+            });
+        }
+
+        #[test]
+        fn test_escrow_ignores_transfer_mode() {
+            let mut deps = prepare_cw721_base_state();
+            let mut env = mock_env();
+            enable_auctions(&mut deps.as_mut(), AuctionDeathRule::Cancel);
+            start_auction(&mut deps.as_mut(), &env, "magotchi1");
+            start_auction(&mut deps.as_mut(), &env, "magotchi2");
+            let _ = bid(&mut deps.as_mut(), &env, "bidder1", 1000).unwrap();
+            Cw721MetadataContract::default()
+                .set_transfer_mode(
+                    &mut deps.storage,
+                    &Addr::unchecked(MINTER),
+                    TransferMode::Soulbound,
+                )
+                .unwrap();
+
+            let _ = execute_cancel_auction(&mut deps.as_mut(), &mock_info(OWNER, &[]), "magotchi2")
+                .unwrap();
+            assert_that!(owner_of(deps.as_ref(), "magotchi2")).is_equal_to(Addr::unchecked(OWNER));
+
+            env.block.time = env.block.time.plus_seconds(3600);
+            let _ = execute_settle_auction(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            assert_that!(owner_of(deps.as_ref(), "magotchi1"))
+                .is_equal_to(Addr::unchecked("bidder1"));
+        }
+
+        #[test]
+        fn test_pause_extends_auction() {
+            let mut deps = prepare_cw721_base_state();
+            let mut env = mock_env();
+            let end_time = env.block.time.plus_seconds(3600);
+            enable_auctions(&mut deps.as_mut(), AuctionDeathRule::Cancel);
+            start_auction(&mut deps.as_mut(), &env, "magotchi1");
+
+            let info = mock_info(MINTER, &[]);
+            env.block.time = env.block.time.plus_seconds(1000);
+            let _ = execute_pause(&mut deps.as_mut(), &env, &info).unwrap();
+            env.block.time = env.block.time.plus_seconds(1000);
+            let _ = execute_unpause(&mut deps.as_mut(), &env, &info).unwrap();
+
+            let auction = query_auction(deps.as_ref(), "magotchi1".to_string()).unwrap();
+            assert_that!(auction.end_time).is_equal_to(end_time.plus_seconds(1000));
+
+            // the paused time is made up for after the original end
+            env.block.time = end_time.plus_seconds(500);
+            let _ = bid(&mut deps.as_mut(), &env, "bidder1", 1000).unwrap();
+            let err = execute_settle_auction(&mut deps.as_mut(), &env, "magotchi1").unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::AuctionNotEnded {
+                token_id: "magotchi1".to_string(),
+            });
+        }
+
+        #[test]
+        fn test_death_during_auction() {
+            for on_death in [AuctionDeathRule::Cancel, AuctionDeathRule::Continue] {
+                let mut deps = prepare_cw721_base_state();
+                let mut env = mock_env();
+                enable_auctions(&mut deps.as_mut(), on_death.clone());
+                let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+                start_auction(&mut deps.as_mut(), &env, "magotchi1");
+                let _ = bid(&mut deps.as_mut(), &env, "bidder1", 1000).unwrap();
+
+                // magotchis in auction are not reaped
+                env.block.time = env.block.time.plus_days(11);
+                let _ = execute_reap(
+                    &mut deps.as_mut(),
+                    Some(vec!["magotchi1".to_string()]),
+                    &env,
+                    &mock_info("reaper", &[]),
+                )
+                .unwrap();
+                assert_that!(owner_of(deps.as_ref(), "magotchi1"))
+                    .is_equal_to(Addr::unchecked(CONTRACT));
+
+                let res = execute_settle_auction(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+                match on_death {
+                    AuctionDeathRule::Cancel => {
+                        assert_that!(bank_messages(&res)).is_equal_to(vec![CosmosMsg::Bank(
+                            BankMsg::Send {
+                                to_address: "bidder1".to_string(),
+                                amount: coins(1000, "uluna"),
+                            },
+                        )]);
+                        assert_that!(owner_of(deps.as_ref(), "magotchi1"))
+                            .is_equal_to(Addr::unchecked(OWNER));
+                        assert_that!(reserved(deps.as_ref())).is_equal_to(Uint128::zero());
+                    }
+                    AuctionDeathRule::Continue => {
+                        assert_that!(bank_messages(&res)).has_length(2);
+                        assert_that!(owner_of(deps.as_ref(), "magotchi1"))
+                            .is_equal_to(Addr::unchecked("bidder1"));
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_bid_on_dead_magotchi() {
+            let mut deps = prepare_cw721_base_state();
+            let mut env = mock_env();
+            enable_auctions(&mut deps.as_mut(), AuctionDeathRule::Cancel);
+            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            let _ = execute_start_auction(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                coin(1000, "uluna"),
+                Uint128::new(100),
+                86400 * 20,
+            )
+            .unwrap();
+
+            env.block.time = env.block.time.plus_days(11);
+            assert_that!(bid(&mut deps.as_mut(), &env, "bidder1", 1000))
+                .is_err()
+                .is_equal_to(ContractError::AuctionCancelled {
+                    token_id: "magotchi1".to_string(),
+                });
+            // without bids, the magotchi is returned to the seller right away
+            let res = execute_settle_auction(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            assert_that!(res.messages).is_empty();
+            assert_that!(owner_of(deps.as_ref(), "magotchi1")).is_equal_to(Addr::unchecked(OWNER));
+        }
+    }
+
+    mod rental {
+        use super::*;
+        use crate::query::query_user_of;
+
+        fn prepare_rental() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+            let mut deps = prepare_cw721_base_state();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.feeding_policy = FeedingPolicy::OwnerAndApproved;
+                    Ok(config)
+                })
+                .unwrap();
+            let _ = execute_hatch(&mut deps.as_mut(), &mock_env(), "magotchi1").unwrap();
+            deps
+        }
 
-            // Verify the new death time
-            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(10));
+        fn lend(deps: &mut DepsMut, env: &Env, price: Option<Coin>) -> CResult<Response> {
+            execute_lend(
+                deps,
+                env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                "friend",
+                Expiration::AtHeight(env.block.height + 100),
+                price,
+            )
+        }
+
+        fn feed(deps: &mut DepsMut, env: &Env, feeder: &str) -> CResult<Response> {
+            execute_feed(
+                deps,
+                env,
+                &mock_info(feeder, &coins(8_000_000, "uluna")),
+                "magotchi1",
+            )
         }
 
         #[test]
-        fn test_execute_feed_insufficient_funds() {
-            let mut deps = prepare();
+        fn test_lend_and_rent() {
+            let mut deps = prepare_rental();
+            let mut env = mock_env();
 
-            // Mock environment and message info
-            let env = mock_env();
-            let info = mock_info("feeder", &coins(500, "uluna")); // Insufficient funds
+            let err = execute_lend(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("friend", &[]),
+                "magotchi1",
+                "friend",
+                Expiration::Never {},
+                None,
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Unauthorized {});
+            let _ = lend(&mut deps.as_mut(), &env, Some(coin(500, "uluna"))).unwrap();
 
-            // Hatch the magotchi first
-            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            // the rental starts once it is paid
+            assert_that!(feed(&mut deps.as_mut(), &env, "friend"))
+                .is_err()
+                .is_equal_to(ContractError::NotAllowedToFeed {});
+            let err = execute_rent(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("stranger", &coins(500, "uluna")),
+                "magotchi1",
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Unauthorized {});
+            let res = execute_rent(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("friend", &coins(500, "uluna")),
+                "magotchi1",
+            )
+            .unwrap();
+            assert_that!(res.messages[0].msg).is_equal_to(CosmosMsg::Bank(BankMsg::Send {
+                to_address: OWNER.to_string(),
+                amount: coins(500, "uluna"),
+            }));
+            assert_that!(
+                query_user_of(deps.as_ref(), env.clone(), "magotchi1".to_string())
+                    .unwrap()
+                    .user
+            )
+            .is_equal_to(Some("friend".to_string()));
 
-            // Execute feed
-            let res = execute_feed(&mut deps.as_mut(), &env, "magotchi1", &info.funds);
+            // the borrower cares for the magotchi and is credited for it
+            let _ = feed(&mut deps.as_mut(), &env, "friend").unwrap();
+            let caretaker = CARETAKERS
+                .load(&deps.storage, ("magotchi1", &Addr::unchecked("friend")))
+                .unwrap();
+            assert_that!(caretaker.feedings).is_equal_to(1);
 
-            // Verify the error
-            assert_that!(res).is_err();
+            // but only the owner can transfer it, or lend it to someone else after the rental
+            let err = crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("friend", &[]),
+                ExecuteMsg::TransferNft {
+                    recipient: "friend".to_string(),
+                    token_id: "magotchi1".to_string(),
+                },
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::Cw721(
+                cw721_base::ContractError::Ownership(cw_ownable::OwnershipError::NotOwner),
+            ));
+            assert_that!(lend(&mut deps.as_mut(), &env, None))
+                .is_err()
+                .is_equal_to(ContractError::RentalActive {
+                    token_id: "magotchi1".to_string(),
+                });
+
+            env.block.height += 100;
+            assert_that!(feed(&mut deps.as_mut(), &env, "friend"))
+                .is_err()
+                .is_equal_to(ContractError::NotAllowedToFeed {});
+            assert_that!(lend(&mut deps.as_mut(), &env, None)).is_ok();
         }
 
         #[test]
-        fn test_execute_feed_unhatched() {
-            let mut deps = prepare();
-
-            // Mock environment and message info
+        fn test_rental_ends_on_transfer() {
+            let mut deps = prepare_rental();
             let env = mock_env();
-            let info = mock_info("feeder", &coins(1000, "uluna"));
-
-            // Execute feed on an unhatched magotchi
-            let res = execute_feed(&mut deps.as_mut(), &env, "magotchi1", &info.funds);
+            let err = execute_lend(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                "friend",
+                Expiration::Never {},
+                None,
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::RentalNeverExpires {});
 
-            // Verify the error
-            assert_that!(res).is_err();
+            let _ = lend(&mut deps.as_mut(), &env, None).unwrap();
+            crate::entry::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(OWNER, &[]),
+                ExecuteMsg::TransferNft {
+                    recipient: "buyer".to_string(),
+                    token_id: "magotchi1".to_string(),
+                },
+            )
+            .unwrap();
+            assert_that!(RENTALS.has(&deps.storage, "magotchi1")).is_false();
+            assert_that!(feed(&mut deps.as_mut(), &env, "friend"))
+                .is_err()
+                .is_equal_to(ContractError::NotAllowedToFeed {});
         }
 
         #[test]
-        fn test_execute_feed_dead() {
-            let mut deps = prepare();
+        fn test_end_rental() {
+            let mut deps = prepare_rental();
+            let env = mock_env();
+            let _ = lend(&mut deps.as_mut(), &env, None).unwrap();
 
-            // Mock environment and message info
-            let mut env = mock_env();
-            let info = mock_info("feeder", &coins(1000, "uluna"));
+            // the owner can't end an active rental, the borrower can
+            let err = execute_end_rental(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::RentalActive {
+                token_id: "magotchi1".to_string(),
+            });
+            let _ = execute_end_rental(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("friend", &[]),
+                "magotchi1",
+            )
+            .unwrap();
+            assert_that!(
+                query_user_of(deps.as_ref(), env.clone(), "magotchi1".to_string())
+                    .unwrap()
+                    .user
+            )
+            .is_none();
 
-            // Hatch the magotchi and simulate it being dead
-            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
-            env.block.time = env.block.time.plus_days(11); // Simulate time passing
+            // unpaid rentals can be ended by the owner
+            let _ = lend(&mut deps.as_mut(), &env, Some(coin(500, "uluna"))).unwrap();
+            let _ = execute_end_rental(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+            )
+            .unwrap();
+            assert_that!(RENTALS.has(&deps.storage, "magotchi1")).is_false();
+        }
+    }
 
-            // Execute feed
-            let res = execute_feed(&mut deps.as_mut(), &env, "magotchi1", &info.funds);
+    mod care {
+        use super::*;
+        use crate::query::query_wellbeing;
+        use crate::state::{CareActionConfig, CareConfig};
 
-            // Verify the error
-            assert_that!(res).is_err();
+        fn play(deps: &mut DepsMut, env: &Env, info: &MessageInfo) -> CResult<Response> {
+            execute_care(deps, env, info, "magotchi1", CareAction::Play)
         }
 
         #[test]
-        fn test_execute_feed_wrong_denomination() {
-            let mut deps = prepare();
-
-            // Mock environment and message info
+        fn test_execute_care() {
+            let mut deps = prepare_cw721_base_state();
             let env = mock_env();
-            let info = mock_info("feeder", &coins(1000, "uusd")); // Wrong denomination
-
-            // Hatch the magotchi first
             let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            let info = mock_info(OWNER, &coins(100, "uluna"));
 
-            // Execute feed
-            let res = execute_feed(&mut deps.as_mut(), &env, "magotchi1", &info.funds);
-
-            // Verify the error
-            assert_that!(res).is_err();
-        }
-
-        #[test]
-        fn test_execute_feed_exact_funds() {
-            let mut deps = prepare();
-
-            // Mock environment and message info
-            let env = mock_env();
-            let info = mock_info("feeder", &coins(8_000_000, "uluna"));
+            assert_that!(play(&mut deps.as_mut(), &env, &info))
+                .is_err()
+                .is_equal_to(ContractError::CareDisabled {});
 
-            // Hatch the magotchi first
-            let _ = execute_hatch(&mut deps.as_mut(), &env, "magotchi1").unwrap();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.care = Some(CareConfig {
+                        play: CareActionConfig {
+                            cost: coins(100, "uluna"),
+                            cooldown: 3600,
+                            decay_per_day: 50,
+                            neglect_weight: 3600,
+                        },
+                        ..Default::default()
+                    });
+                    Ok(config)
+                })
+                .unwrap();
 
-            // Execute feed
-            let res = execute_feed(&mut deps.as_mut(), &env, "magotchi1", &info.funds).unwrap();
+            // only the owner or the borrower can care for the magotchi
+            assert_that!(play(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info("stranger", &coins(100, "uluna"))
+            ))
+            .is_err()
+            .is_equal_to(ContractError::Unauthorized {});
+            assert_that!(play(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &coins(50, "uluna"))
+            ))
+            .is_err()
+            .is_equal_to(ContractError::InvalidPayment {
+                payed: coin(50, "uluna"),
+                expected: coin(100, "uluna"),
+            });
 
-            // Verify the response
+            let res = play(&mut deps.as_mut(), &env, &info).unwrap();
             assert_that!(res.attributes)
-                .is_equal_to(vec![attr("action", "feed"), attr("token_id", "magotchi1")]);
+                .is_equal_to(vec![attr("action", "play"), attr("token_id", "magotchi1")]);
+            assert_that!(play(&mut deps.as_mut(), &env, &info))
+                .is_err()
+                .is_equal_to(ContractError::CareCooldown {
+                    ready_at: env.block.time.plus_seconds(3600),
+                });
 
-            // Verify the state changes
-            let state = LIVE_STATES
-                .load(&deps.storage, "magotchi1".to_string())
-                .unwrap();
-            assert_that!(state.is_hatched()).is_true();
-            assert_that!(state.is_dead(&env.block)).is_false();
-            assert_that!(state.hatched_at())
-                .is_some()
-                .is_equal_to(env.block.time);
+            // free actions need no payment
+            assert_that!(execute_care(
+                &mut deps.as_mut(),
+                &env,
+                &mock_info(OWNER, &[]),
+                "magotchi1",
+                CareAction::Clean
+            ))
+            .is_ok();
 
-            // Verify the new death time
-            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(10));
+            // a day before its death, the health of the magotchi is 20%
+            let wellbeing =
+                query_wellbeing(deps.as_ref(), env.clone(), "magotchi1".to_string()).unwrap();
+            assert_that!(wellbeing.happiness).is_equal_to(100);
+            assert_that!(wellbeing.wellbeing).is_equal_to(80);
         }
     }
 
-    mod reap {
-        use cw721_base::InstantiateMsg;
-
-        use crate::{ExecuteMsg, CONTRACT_NAME};
-        const SYMBOL: &str = "MAG";
-        const MINTER: &str = "minter";
-
+    mod items {
         use super::*;
+        use crate::msg::InventoryItem;
+        use crate::query::{query_health, query_inventory, query_nft_info};
+        use crate::state::{ItemConfig, ItemEffect, SicknessConfig};
 
-        fn mint_msg(token_id: &str) -> ExecuteMsg {
-            ExecuteMsg::Mint {
-                token_id: token_id.to_string(),
-                owner: "test_user".to_string(),
-                token_uri: None,
-                extension: None,
-            }
-        }
-
-        fn setup_cw721_base() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
-            let mut deps = mock_dependencies();
-            let contract = Cw721MetadataContract::default();
-            let msg = InstantiateMsg {
-                name: CONTRACT_NAME.to_string(),
-                symbol: SYMBOL.to_string(),
-                minter: Some(String::from(MINTER)),
-                withdraw_address: None,
+        fn prepare_items() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+            let mut deps = prepare_cw721_base_state();
+            let item = |name: &str, price: u128, effect| ItemConfig {
+                name: name.to_string(),
+                price: coins(price, "uluna"),
+                effect,
             };
-
-            let info = mock_info("creator", &[]);
-            let res = contract
-                .instantiate(deps.as_mut(), mock_env(), info, msg)
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.items = vec![
+                        item("snack", 500, ItemEffect::Nourish { days: 1 }),
+                        item("medicine", 2000, ItemEffect::Heal {}),
+                        item("revive_potion", 10000, ItemEffect::Revive {}),
+                    ];
+                    Ok(config)
+                })
                 .unwrap();
-            assert_eq!(0, res.messages.len());
+            let _ = execute_hatch(&mut deps.as_mut(), &mock_env(), "magotchi1").unwrap();
+            deps
+        }
 
-            for token in TEST_TOKENS.iter() {
-                let msg = mint_msg(token);
-                let info = mock_info(MINTER, &[]);
-                let _ = contract
-                    .execute(deps.as_mut(), mock_env(), info, msg)
-                    .unwrap();
-            }
-            return deps;
+        fn use_item(deps: &mut DepsMut, env: &Env, item: &str) -> CResult<Response> {
+            execute_use_item(deps, env, &mock_info(OWNER, &[]), "magotchi1", item)
         }
 
-        fn prepare_cw721_base_state() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
-            let mut deps = setup_cw721_base();
-            setup_contract(&mut deps.as_mut());
-            return deps;
+        fn inventory(deps: Deps) -> Vec<InventoryItem> {
+            query_inventory(deps, OWNER.to_string(), None, None)
+                .unwrap()
+                .items
         }
 
         #[test]
-        fn test_execute_reap() {
-            let mut deps = prepare_cw721_base_state();
-
-            // Mock environment and message info
-            let mut env = mock_env();
+        fn test_buy_item() {
+            let mut deps = prepare_items();
 
-            // Hatch the magotchis and simulate them being dead
-            for &token in TEST_TOKENS.iter() {
-                let _ = execute_hatch(&mut deps.as_mut(), &env, token).unwrap();
-                let state = LIVE_STATES.load(&deps.storage, token.to_string()).unwrap();
-                let state = Gotchi::custom_min_1sec(
-                    state.hatched_at().unwrap().seconds() / (24 * 60 * 60),
-                    env.block.time.plus_seconds(1).seconds() / (24 * 60 * 60),
-                );
-                LIVE_STATES
-                    .save(&mut deps.storage, token.to_string(), &state)
-                    .unwrap();
-            }
-            env.block.time = env.block.time.plus_days(2); // Simulate time passing
+            let err = execute_buy_item(
+                &mut deps.as_mut(),
+                &mock_info(OWNER, &coins(500, "uluna")),
+                "cake",
+                1,
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::UnknownItem {
+                item: "cake".to_string(),
+            });
+            let err = execute_buy_item(
+                &mut deps.as_mut(),
+                &mock_info(OWNER, &coins(500, "uluna")),
+                "snack",
+                0,
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::InvalidAmount {});
 
-            // Execute reap
-            let res = execute_reap(&mut deps.as_mut(), None, &env).unwrap();
+            // the price of all items must be paid
+            let err = execute_buy_item(
+                &mut deps.as_mut(),
+                &mock_info(OWNER, &coins(500, "uluna")),
+                "snack",
+                2,
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::InvalidPayment {
+                payed: coin(500, "uluna"),
+                expected: coin(1000, "uluna"),
+            });
+            let _ = execute_buy_item(
+                &mut deps.as_mut(),
+                &mock_info(OWNER, &coins(1000, "uluna")),
+                "snack",
+                2,
+            )
+            .unwrap();
+            assert_that!(inventory(deps.as_ref())).is_equal_to(vec![InventoryItem {
+                item: "snack".to_string(),
+                amount: 2,
+            }]);
+        }
 
-            // Verify the response
-            assert_that!(res.attributes).is_equal_to(vec![
-                attr("action", "reap"),
-                attr("tokens", "magotchi1,magotchi2,magotchi3"),
-            ]);
+        #[test]
+        fn test_buy_item_overflow() {
+            let mut deps = prepare_items();
+            INVENTORIES
+                .save(
+                    &mut deps.storage,
+                    (&Addr::unchecked(OWNER), "snack"),
+                    &(u32::MAX - 1),
+                )
+                .unwrap();
+            let err = execute_buy_item(
+                &mut deps.as_mut(),
+                &mock_info(OWNER, &coins(1000, "uluna")),
+                "snack",
+                2,
+            )
+            .unwrap_err();
+            assert_that!(err).is_equal_to(ContractError::InvalidAmount {});
 
-            // Verify the state changes
-            for &token in TEST_TOKENS.iter() {
-                let state = LIVE_STATES.load(&deps.storage, token.to_string()).unwrap();
-                assert_that!(state.is_dead(&env.block)).is_true();
-            }
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.items[0].price = coins(u128::MAX, "uluna");
+                    Ok(config)
+                })
+                .unwrap();
+            assert_that!(execute_buy_item(
+                &mut deps.as_mut(),
+                &mock_info(OWNER, &coins(1000, "uluna")),
+                "snack",
+                2,
+            ))
+            .is_err();
         }
 
         #[test]
-        fn test_execute_reap_not_all_dead() {
-            let mut deps = prepare();
-
-            // Mock environment and message info
+        fn test_use_item() {
+            let mut deps = prepare_items();
             let mut env = mock_env();
-
-            // Hatch the magotchis and simulate only some being dead
-            for &token in TEST_TOKENS[..2].iter() {
-                let _ = execute_hatch(&mut deps.as_mut(), &env, token).unwrap();
-                let state = LIVE_STATES.load(&deps.storage, token.to_string()).unwrap();
-                let state = Gotchi::custom_min_1sec(
-                    state.hatched_at().unwrap().seconds() / (24 * 60 * 60),
-                    env.block.time.plus_seconds(1).seconds() / (24 * 60 * 60),
-                );
-                LIVE_STATES
-                    .save(&mut deps.storage, token.to_string(), &state)
-                    .unwrap();
-                env.block.time = env.block.time.plus_days(2); // Simulate time passing
-
-                // Execute reap
-                let res = execute_reap(&mut deps.as_mut(), None, &env);
-
-                // Verify the error
-                assert_that!(res).is_err();
+            for (item, price) in [("snack", 1000), ("revive_potion", 10000)] {
+                let _ = execute_buy_item(
+                    &mut deps.as_mut(),
+                    &mock_info(OWNER, &coins(price, "uluna")),
+                    item,
+                    if item == "snack" { 2 } else { 1 },
+                )
+                .unwrap();
             }
-        }
 
-        #[test]
-        fn test_execute_reap_empty_tokens() {
-            let mut deps = prepare();
+            assert_that!(use_item(&mut deps.as_mut(), &env, "medicine"))
+                .is_err()
+                .is_equal_to(ContractError::InsufficientItems {
+                    item: "medicine".to_string(),
+                });
 
-            // Mock environment and message info
-            let env = mock_env();
+            // a snack gives the magotchi another day
+            let _ = use_item(&mut deps.as_mut(), &env, "snack").unwrap();
+            let state = load_gotchi(&deps.storage, "magotchi1").unwrap();
+            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(2));
 
-            // Execute reap with empty tokens list
-            let res = execute_reap(&mut deps.as_mut(), Some(vec![]), &env).unwrap();
+            // items that have no effect are not used up
+            assert_that!(use_item(&mut deps.as_mut(), &env, "revive_potion"))
+                .is_err()
+                .is_equal_to(ContractError::MagotchiNotDead {});
+            env.block.time = env.block.time.plus_days(3);
+            assert_that!(use_item(&mut deps.as_mut(), &env, "snack"))
+                .is_err()
+                .is_equal_to(ContractError::MagotchiDied {});
 
-            // Verify the response
-            assert_that!(res.attributes)
-                .is_equal_to(vec![attr("action", "reap"), attr("tokens", "")]);
+            let _ = use_item(&mut deps.as_mut(), &env, "revive_potion").unwrap();
+            let state = load_gotchi(&deps.storage, "magotchi1").unwrap();
+            assert_that!(state.is_dead(&env.block)).is_false();
+            assert_that!(state.revivals()).is_equal_to(1);
+            assert_that!(inventory(deps.as_ref())).is_equal_to(vec![InventoryItem {
+                item: "snack".to_string(),
+                amount: 1,
+            }]);
         }
 
         #[test]
-        fn test_execute_reap_some_dead() {
-            let mut deps = prepare_cw721_base_state();
-
-            // Mock environment and message info
-            let mut env = mock_env();
-
-            // Hatch the magotchis and simulate only some being dead
-            for &token in TEST_TOKENS[..2].iter() {
-                let _ = execute_hatch(&mut deps.as_mut(), &env, token).unwrap();
-                let state = LIVE_STATES.load(&deps.storage, token.to_string()).unwrap();
-                let state = Gotchi::custom_min_1sec(
-                    state.hatched_at().unwrap().seconds() / (24 * 60 * 60),
-                    env.block.time.plus_seconds(1).seconds() / (24 * 60 * 60),
-                );
-                LIVE_STATES
-                    .save(&mut deps.storage, token.to_string(), &state)
-                    .unwrap();
+        fn test_medicine_cures_sickness() {
+            let mut deps = prepare_items();
+            let env = mock_env();
+            CONFIG
+                .update(&mut deps.storage, |mut config| -> StdResult<_> {
+                    config.sickness = Some(SicknessConfig {
+                        min_days_unfed: 3,
+                        chance: 1000,
+                        multiplier: 2000,
+                    });
+                    Ok(config)
+                })
+                .unwrap();
+            for (item, price) in [("snack", 500), ("medicine", 2000)] {
+                let _ = execute_buy_item(
+                    &mut deps.as_mut(),
+                    &mock_info(OWNER, &coins(price, "uluna")),
+                    item,
+                    1,
+                )
+                .unwrap();
             }
-            env.block.time = env.block.time.plus_days(2); // Simulate time passing
 
-            // Execute reap
-            let res = execute_reap(
-                &mut deps.as_mut(),
-                Some(vec!["magotchi1".to_string(), "magotchi2".to_string()]),
-                &env,
-            )
-            .unwrap();
+            // the hungry magotchi gets sick when it is touched, halving the rest of its life
+            let _ = use_item(&mut deps.as_mut(), &env, "snack").unwrap();
+            let state = load_gotchi(&deps.storage, "magotchi1").unwrap();
+            assert_that!(state.is_sick()).is_true();
+            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_seconds(36 * 60 * 60));
+            let health = query_health(deps.as_ref(), env.clone(), "magotchi1".to_string());
+            assert_that!(health.unwrap().sick).is_true();
+            // the magotchi was minted without metadata, but still shows the status
+            let info = query_nft_info(deps.as_ref(), "magotchi1".to_string()).unwrap();
+            assert_that!(info.extension.unwrap().attributes).is_equal_to(Some(vec![Trait {
+                display_type: None,
+                trait_type: "status".to_string(),
+                value: "sick".to_string(),
+            }]));
 
-            // Verify the response
-            assert_that!(res.attributes).is_equal_to(vec![
-                attr("action", "reap"),
-                attr("tokens", "magotchi1,magotchi2"),
-            ]);
+            let _ = use_item(&mut deps.as_mut(), &env, "medicine").unwrap();
+            let state = load_gotchi(&deps.storage, "magotchi1").unwrap();
+            assert_that!(state.is_sick()).is_false();
+            assert_that!(state.death_time()).is_equal_to(env.block.time.plus_days(10));
         }
     }
 
@@ -567,7 +5110,7 @@ mod tests {
             env.block.time = env.block.time.plus_days(2); // Simulate time passing
 
             // Get all dead tokens
-            let dead_tokens = get_all_dead(deps.as_ref(), &env.block);
+            let dead_tokens = get_all_dead(deps.as_ref(), &env.block).unwrap();
 
             // Verify the result
             assert_that!(dead_tokens).is_equal_to(vec![
@@ -591,7 +5134,7 @@ mod tests {
             // Mint a new token
             let token_id = "new_magotchi".to_string();
 
-            assert_that!(execute_mint(&mut deps.as_mut(), token_id.clone())).is_ok();
+            assert_that!(execute_mint(&mut deps.as_mut(), token_id.clone(), &None)).is_ok();
 
             // Verify the state changes
             let state = LIVE_STATES
@@ -0,0 +1,219 @@
+use cosmwasm_std::Env;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    state::{GeneticsConfig, Genome, TraitTable},
+    Trait,
+};
+
+/// Generates the genome of a magotchi. The dna is derived from the token_id, the block and the
+/// contract address, so the same magotchi hatched in the same block always gets the same genes
+pub fn generate_genome(config: &GeneticsConfig, token_id: &str, env: &Env) -> Genome {
+    let dna: [u8; 32] = Sha256::new()
+        .chain_update(token_id.as_bytes())
+        .chain_update(env.block.height.to_be_bytes())
+        .chain_update(env.block.time.nanos().to_be_bytes())
+        .chain_update(env.block.chain_id.as_bytes())
+        .chain_update(env.contract.address.as_bytes())
+        .finalize()
+        .into();
+    genome_from_dna(config, dna)
+}
+
+/// Selects the traits of a magotchi with the given dna, e.g. the seed of a randomness round
+pub fn genome_from_dna(config: &GeneticsConfig, dna: [u8; 32]) -> Genome {
+    build_genome(config, dna, |_| None)
+}
+
+/// Generates the genome of a bred egg. The traits it inherited from its parents are kept, only
+/// the traits neither parent had are picked with dna derived from the dna of the parents
+pub fn inherit_genome(
+    config: &GeneticsConfig,
+    token_id: &str,
+    parents: &[Option<&Genome>],
+    inherited: &[Trait],
+) -> Genome {
+    let dna: [u8; 32] = parents
+        .iter()
+        .fold(
+            Sha256::new().chain_update(token_id.as_bytes()),
+            |hasher, genome| hasher.chain_update(genome.map_or("", |genome| genome.dna.as_str())),
+        )
+        .finalize()
+        .into();
+    build_genome(config, dna, |table| {
+        let inherited = inherited
+            .iter()
+            .find(|t| t.trait_type == table.trait_type)?;
+        let option = table
+            .options
+            .iter()
+            .find(|option| option.value == inherited.value)?;
+        Some((
+            option.value.clone(),
+            option.hunger_multiplier.unwrap_or(1000),
+        ))
+    })
+}
+
+/// Builds a genome, taking the trait of every table from `inherited` or else picking it with the dna
+fn build_genome(
+    config: &GeneticsConfig,
+    dna: [u8; 32],
+    inherited: impl Fn(&TraitTable) -> Option<(String, u64)>,
+) -> Genome {
+    let mut hunger_multiplier: u64 = 1000;
+    let mut traits = vec![];
+    for (index, table) in config.traits.iter().enumerate() {
+        let picked = inherited(table).or_else(|| pick(table, roll(&dna, index as u32)));
+        if let Some((value, multiplier)) = picked {
+            hunger_multiplier = hunger_multiplier.saturating_mul(multiplier) / 1000;
+            traits.push(Trait {
+                display_type: None,
+                trait_type: table.trait_type.clone(),
+                value,
+            });
+        }
+    }
+
+    Genome {
+        dna: dna.iter().map(|byte| format!("{byte:02x}")).collect(),
+        traits,
+        hunger_multiplier,
+    }
+}
+
+/// Rolls a random number for the trait table at the given index
+fn roll(dna: &[u8; 32], index: u32) -> u64 {
+    let hash = Sha256::new()
+        .chain_update(dna)
+        .chain_update(index.to_be_bytes())
+        .finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+/// Picks a value from the table, returning it with its hunger multiplier
+fn pick(table: &TraitTable, roll: u64) -> Option<(String, u64)> {
+    let total: u64 = table
+        .options
+        .iter()
+        .map(|option| option.weight as u64)
+        .sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut remaining = roll % total;
+    for option in table.options.iter() {
+        if remaining < option.weight as u64 {
+            return Some((
+                option.value.clone(),
+                option.hunger_multiplier.unwrap_or(1000),
+            ));
+        }
+        remaining -= option.weight as u64;
+    }
+    None
+}
+
+/// Merges the traits into the attributes, replacing the attributes with the same trait_type
+pub fn merge_traits(attributes: Option<Vec<Trait>>, traits: &[Trait]) -> Vec<Trait> {
+    let mut attributes: Vec<Trait> = attributes
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|attribute| !traits.iter().any(|t| t.trait_type == attribute.trait_type))
+        .collect();
+    attributes.extend(traits.iter().cloned());
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::WeightedTrait;
+    use cosmwasm_std::testing::mock_env;
+    use speculoos::prelude::*;
+
+    fn option(value: &str, weight: u32, hunger_multiplier: Option<u64>) -> WeightedTrait {
+        WeightedTrait {
+            value: value.to_string(),
+            weight,
+            hunger_multiplier,
+        }
+    }
+
+    fn config() -> GeneticsConfig {
+        GeneticsConfig {
+            traits: vec![
+                TraitTable {
+                    trait_type: "color".to_string(),
+                    options: vec![option("red", 1, None), option("blue", 1, None)],
+                },
+                TraitTable {
+                    trait_type: "species".to_string(),
+                    options: vec![option("newt", 1, Some(500)), option("axolotl", 0, None)],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_generate_genome_is_deterministic() {
+        let env = mock_env();
+        let genome = generate_genome(&config(), "magotchi1", &env);
+
+        assert_that!(genome).is_equal_to(generate_genome(&config(), "magotchi1", &env));
+        assert_that!(genome.dna.len()).is_equal_to(64);
+        assert_that!(genome.traits).has_length(2);
+        assert_that!(genome.traits[1].value).is_equal_to("newt".to_string());
+        assert_that!(genome.hunger_multiplier).is_equal_to(500);
+
+        let mut other = mock_env();
+        other.block.height += 1;
+        assert_that!(generate_genome(&config(), "magotchi1", &other).dna)
+            .is_not_equal_to(genome.dna.clone());
+        assert_that!(generate_genome(&config(), "magotchi2", &env).dna).is_not_equal_to(genome.dna);
+    }
+
+    #[test]
+    fn test_pick() {
+        let table = TraitTable {
+            trait_type: "color".to_string(),
+            options: vec![option("red", 1, None), option("blue", 3, Some(1200))],
+        };
+
+        assert_that!(pick(&table, 0)).is_equal_to(Some(("red".to_string(), 1000)));
+        assert_that!(pick(&table, 1)).is_equal_to(Some(("blue".to_string(), 1200)));
+        assert_that!(pick(&table, 7)).is_equal_to(Some(("blue".to_string(), 1200)));
+        assert_that!(pick(&table, 8)).is_equal_to(Some(("red".to_string(), 1000)));
+
+        let empty = TraitTable {
+            trait_type: "color".to_string(),
+            options: vec![],
+        };
+        assert_that!(pick(&empty, 0)).is_none();
+    }
+
+    #[test]
+    fn test_merge_traits() {
+        let attribute = |trait_type: &str, value: &str| Trait {
+            display_type: None,
+            trait_type: trait_type.to_string(),
+            value: value.to_string(),
+        };
+
+        let merged = merge_traits(
+            Some(vec![
+                attribute("generation", "1"),
+                attribute("color", "red"),
+            ]),
+            &[attribute("color", "blue")],
+        );
+        assert_that!(merged).is_equal_to(vec![
+            attribute("generation", "1"),
+            attribute("color", "blue"),
+        ]);
+    }
+}
@@ -4,14 +4,20 @@ pub use cw721_base::{InstantiateMsg, MinterResponse};
 use msg::{MagotchiExecuteExtension, MagotchiQueryExtension};
 pub mod error;
 pub mod execute;
+pub mod genetics;
+pub mod merkle;
 pub mod msg;
 pub mod query;
+pub mod randomness;
 pub mod state;
 pub mod utils;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod interface;
 
+#[cfg(test)]
+mod multi_tests;
+
 // Version info for migration
 const CONTRACT_NAME: &str = "crates.io:cw721-gotchi";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -60,11 +66,22 @@ pub mod entry {
     #[cfg(not(feature = "library"))]
     use cosmwasm_std::entry_point;
     use cosmwasm_std::{
-        coin, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+        coin, to_json_binary, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
     };
     use error::ContractError;
-    use execute::{execute_feed, execute_hatch, execute_mint, execute_reap, execute_update_config};
-    use state::{Config, CONFIG};
+    use execute::{
+        assert_not_paused, assert_transferable, clear_feeding_allowlists, end_rentals, execute_bid,
+        execute_breed, execute_buy, execute_buy_item, execute_cancel_auction, execute_care,
+        execute_commit_randomness, execute_commit_reveal, execute_delist, execute_end_rental,
+        execute_feed, execute_hatch, execute_lend, execute_list, execute_mint, execute_pause,
+        execute_pay_hunger_tax, execute_presale_mint, execute_public_mint, execute_reap,
+        execute_rent, execute_reveal, execute_reveal_randomness, execute_revive,
+        execute_set_token_royalties, execute_settle_auction, execute_start_auction,
+        execute_start_vacation, execute_unpause, execute_update_config,
+        execute_update_feeding_allowlist, execute_use_item, execute_withdraw_treasury,
+        remove_listings,
+    };
+    use state::{CareAction, Config, FeedingPolicy, CONFIG, PAUSE};
 
     // This makes a conscious choice on the various generics used by the contract
     #[cfg_attr(not(feature = "library"), entry_point)]
@@ -80,6 +97,23 @@ pub mod entry {
             max_unfed_days: 10,
             feeding_cost_multiplier: 1,
             graveyard: info.sender.clone(),
+            caretaker_rewards: None,
+            feeding_policy: FeedingPolicy::Open,
+            hunger_tax: false,
+            revival: None,
+            vacation: None,
+            breeding: None,
+            genetics: None,
+            public_mint: None,
+            presale: None,
+            treasury: None,
+            royalties: None,
+            reveal: None,
+            marketplace: None,
+            care: None,
+            items: vec![],
+            sickness: None,
+            randomness: None,
         };
         initial_config.validate()?;
         CONFIG.save(deps.storage, &initial_config)?;
@@ -99,24 +133,211 @@ pub mod entry {
         match msg.clone() {
             ExecuteMsg::Extension { msg } => match msg {
                 MagotchiExecuteExtension::Feed { token_id } => {
-                    execute_feed(&mut deps, &env, &token_id, &info.funds)
+                    execute_feed(&mut deps, &env, &info, &token_id)
                 }
                 MagotchiExecuteExtension::Hatch { token_id } => {
                     execute_hatch(&mut deps, &env, &token_id)
                 }
-                MagotchiExecuteExtension::Reap { tokens } => execute_reap(&mut deps, tokens, &env),
+                MagotchiExecuteExtension::Reap { tokens } => {
+                    execute_reap(&mut deps, tokens, &env, &info)
+                }
                 MagotchiExecuteExtension::UpdateConfig {
                     config: partial_config,
-                } => execute_update_config(&mut deps, partial_config),
+                } => execute_update_config(&mut deps, &info, partial_config),
+                MagotchiExecuteExtension::AllowFeeder { token_id, feeder } => {
+                    execute_update_feeding_allowlist(
+                        &mut deps, &env, &info, &token_id, &feeder, true,
+                    )
+                }
+                MagotchiExecuteExtension::DisallowFeeder { token_id, feeder } => {
+                    execute_update_feeding_allowlist(
+                        &mut deps, &env, &info, &token_id, &feeder, false,
+                    )
+                }
+                MagotchiExecuteExtension::Revive { token_id } => {
+                    execute_revive(&mut deps, &env, &info, &token_id)
+                }
+                MagotchiExecuteExtension::BuyItem { item, amount } => {
+                    execute_buy_item(&mut deps, &info, &item, amount)
+                }
+                MagotchiExecuteExtension::Use { token_id, item } => {
+                    execute_use_item(&mut deps, &env, &info, &token_id, &item)
+                }
+                MagotchiExecuteExtension::Play { token_id } => {
+                    execute_care(&mut deps, &env, &info, &token_id, CareAction::Play)
+                }
+                MagotchiExecuteExtension::Clean { token_id } => {
+                    execute_care(&mut deps, &env, &info, &token_id, CareAction::Clean)
+                }
+                MagotchiExecuteExtension::Sleep { token_id } => {
+                    execute_care(&mut deps, &env, &info, &token_id, CareAction::Sleep)
+                }
+                MagotchiExecuteExtension::StartVacation { token_id, days } => {
+                    execute_start_vacation(&mut deps, &env, &info, &token_id, days)
+                }
+                MagotchiExecuteExtension::Pause {} => execute_pause(&mut deps, &env, &info),
+                MagotchiExecuteExtension::Unpause {} => execute_unpause(&mut deps, &env, &info),
+                MagotchiExecuteExtension::Breed { parent_a, parent_b } => {
+                    execute_breed(&mut deps, &env, &info, &parent_a, &parent_b)
+                }
+                MagotchiExecuteExtension::PublicMint {} => {
+                    execute_public_mint(&mut deps, &env, &info)
+                }
+                MagotchiExecuteExtension::PresaleMint { proof } => {
+                    execute_presale_mint(&mut deps, &env, &info, &proof)
+                }
+                MagotchiExecuteExtension::CommitReveal { commitment } => {
+                    execute_commit_reveal(&mut deps, &info, &commitment)
+                }
+                MagotchiExecuteExtension::Reveal {
+                    token_ids,
+                    metadata,
+                } => execute_reveal(&mut deps, token_ids, metadata),
+                MagotchiExecuteExtension::SetTokenRoyalties {
+                    token_id,
+                    royalties,
+                } => execute_set_token_royalties(&mut deps, &info, &token_id, royalties),
+                MagotchiExecuteExtension::List {
+                    token_id,
+                    price,
+                    expires,
+                } => execute_list(&mut deps, &env, &info, &token_id, price, expires),
+                MagotchiExecuteExtension::Delist { token_id } => {
+                    execute_delist(&mut deps, &env, &info, &token_id)
+                }
+                MagotchiExecuteExtension::Buy { token_id } => {
+                    execute_buy(&mut deps, &env, &info, &token_id)
+                }
+                MagotchiExecuteExtension::StartAuction {
+                    token_id,
+                    reserve_price,
+                    min_increment,
+                    duration,
+                } => execute_start_auction(
+                    &mut deps,
+                    &env,
+                    &info,
+                    &token_id,
+                    reserve_price,
+                    min_increment,
+                    duration,
+                ),
+                MagotchiExecuteExtension::Bid { token_id } => {
+                    execute_bid(&mut deps, &env, &info, &token_id)
+                }
+                MagotchiExecuteExtension::SettleAuction { token_id } => {
+                    execute_settle_auction(&mut deps, &env, &token_id)
+                }
+                MagotchiExecuteExtension::CancelAuction { token_id } => {
+                    execute_cancel_auction(&mut deps, &info, &token_id)
+                }
+                MagotchiExecuteExtension::Lend {
+                    token_id,
+                    to,
+                    until,
+                    price,
+                } => execute_lend(&mut deps, &env, &info, &token_id, &to, until, price),
+                MagotchiExecuteExtension::Rent { token_id } => {
+                    execute_rent(&mut deps, &env, &info, &token_id)
+                }
+                MagotchiExecuteExtension::EndRental { token_id } => {
+                    execute_end_rental(&mut deps, &env, &info, &token_id)
+                }
+                MagotchiExecuteExtension::CommitRandomness {
+                    token_id,
+                    commitment,
+                } => execute_commit_randomness(&mut deps, &env, &info, &token_id, &commitment),
+                MagotchiExecuteExtension::RevealRandomness { token_id, secret } => {
+                    execute_reveal_randomness(&mut deps, &env, &info, &token_id, &secret)
+                }
             },
             ExecuteMsg::Mint {
                 token_id,
                 owner: _,
                 token_uri: _,
-                extension: _,
+                extension,
             } => {
                 // Initialize the live state for the token. No need to check if it already exists, cause the cw721 base contract will fail if it does.
-                execute_mint(deps.borrow_mut(), token_id)?;
+                execute_mint(deps.borrow_mut(), token_id, &extension)?;
+                Cw721MetadataContract::default()
+                    .execute(deps, env, info, msg)
+                    .map_err(ContractError::from)
+            }
+
+            ExecuteMsg::BatchMint { mints } => {
+                for mint in mints {
+                    execute_mint(deps.borrow_mut(), mint.token_id, &mint.extension)?;
+                }
+                Cw721MetadataContract::default()
+                    .execute(deps, env, info, msg)
+                    .map_err(ContractError::from)
+            }
+
+            ExecuteMsg::WithdrawFunds {
+                amount: Coin { denom, .. },
+            }
+            | ExecuteMsg::WithdrawAll { denom } => {
+                // with a treasury, the revenue is split between its recipients instead
+                let config = CONFIG.load(deps.storage)?;
+                match config.treasury {
+                    Some(_) => {
+                        Cw721MetadataContract::default()
+                            .assert_can_withdraw(deps.storage, &info.sender)?;
+                        execute_withdraw_treasury(&mut deps, &config, &denom)
+                    }
+                    None => Cw721MetadataContract::default()
+                        .execute(deps, env, info, msg)
+                        .map_err(ContractError::from),
+                }
+            }
+
+            ExecuteMsg::TransferNft { token_id, .. } | ExecuteMsg::SendNft { token_id, .. } => {
+                assert_not_paused(deps.storage)?;
+                // the hunger tax can be paid with the transfer, feeding the magotchi first
+                let fed = match info.funds.is_empty() {
+                    true => Response::default(),
+                    false => execute_pay_hunger_tax(&mut deps, &env, &info, &token_id)?,
+                };
+                assert_transferable(deps.storage, &env.block, &[token_id.clone()])?;
+                // transferred magotchis are no longer for sale, and their feeders and rentals start
+                // over
+                remove_listings(deps.storage, &[token_id.clone()])?;
+                clear_feeding_allowlists(deps.storage, &[token_id.clone()])?;
+                end_rentals(deps.storage, &[token_id]);
+                let res = Cw721MetadataContract::default().execute(deps, env, info, msg)?;
+                Ok(res
+                    .add_submessages(fed.messages)
+                    .add_attributes(fed.attributes))
+            }
+
+            ExecuteMsg::BatchTransfer { token_ids, .. } => {
+                assert_not_paused(deps.storage)?;
+                assert_transferable(deps.storage, &env.block, &token_ids)?;
+                remove_listings(deps.storage, &token_ids)?;
+                clear_feeding_allowlists(deps.storage, &token_ids)?;
+                end_rentals(deps.storage, &token_ids);
+                Cw721MetadataContract::default()
+                    .execute(deps, env, info, msg)
+                    .map_err(ContractError::from)
+            }
+
+            ExecuteMsg::Burn { token_id } => {
+                remove_listings(deps.storage, &[token_id])?;
+                Cw721MetadataContract::default()
+                    .execute(deps, env, info, msg)
+                    .map_err(ContractError::from)
+            }
+
+            ExecuteMsg::BatchBurn { token_ids } => {
+                remove_listings(deps.storage, &token_ids)?;
+                Cw721MetadataContract::default()
+                    .execute(deps, env, info, msg)
+                    .map_err(ContractError::from)
+            }
+
+            // approved addresses must not be able to move magotchis the transfer mode locks
+            ExecuteMsg::Approve { token_id, .. } => {
+                assert_transferable(deps.storage, &env.block, &[token_id])?;
                 Cw721MetadataContract::default()
                     .execute(deps, env, info, msg)
                     .map_err(ContractError::from)
@@ -131,30 +352,164 @@ pub mod entry {
     #[cfg_attr(not(feature = "library"), entry_point)]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         match msg {
-            QueryMsg::Extension { msg } => match msg {
-                MagotchiQueryExtension::Health { token_id } => {
-                    to_json_binary(&query::query_health(deps, env, token_id)?)
-                }
-                MagotchiQueryExtension::FeedingCost { token_id } => {
-                    to_json_binary(&query::query_feeding_cost(deps, env, token_id)?)
-                }
-                MagotchiQueryExtension::Config {} => to_json_binary(&query::query_config(deps)?),
-                MagotchiQueryExtension::HatchedAt { token_id } => {
-                    to_json_binary(&query::query_hatched_at(deps, token_id)?)
-                }
-                MagotchiQueryExtension::DeathTime { token_id } => {
-                    to_json_binary(&query::query_death_time(deps, token_id)?)
-                }
-                MagotchiQueryExtension::IsHatched { token_id } => {
-                    to_json_binary(&query::query_is_hatched(deps, token_id)?)
-                }
-                MagotchiQueryExtension::GotchiState { token_id } => {
-                    to_json_binary(&query::query_gotchi_state(deps, token_id)?)
-                }
-            },
+            QueryMsg::Extension { msg } => {
+                // while paused, time stands still for the magotchis
+                let mut env = env;
+                env.block = PAUSE
+                    .may_load(deps.storage)?
+                    .unwrap_or_default()
+                    .block(&env.block);
+                query_extension(deps, env, msg)
+            }
+            // mystery boxes show the placeholder metadata until they are revealed
+            QueryMsg::NftInfo { token_id } => {
+                to_json_binary(&query::query_nft_info(deps, token_id)?)
+            }
+            QueryMsg::AllNftInfo {
+                token_id,
+                include_expired,
+            } => to_json_binary(&query::query_all_nft_info(
+                deps,
+                env,
+                token_id,
+                include_expired.unwrap_or(false),
+            )?),
             _ => Cw721MetadataContract::default().query(deps, env, msg),
         }
     }
+
+    fn query_extension(deps: Deps, env: Env, msg: MagotchiQueryExtension) -> StdResult<Binary> {
+        match msg {
+            MagotchiQueryExtension::Health { token_id } => {
+                to_json_binary(&query::query_health(deps, env, token_id)?)
+            }
+            MagotchiQueryExtension::FeedingCost { token_id } => {
+                to_json_binary(&query::query_feeding_cost(deps, env, token_id)?)
+            }
+            MagotchiQueryExtension::Config {} => to_json_binary(&query::query_config(deps)?),
+            MagotchiQueryExtension::HatchedAt { token_id } => {
+                to_json_binary(&query::query_hatched_at(deps, token_id)?)
+            }
+            MagotchiQueryExtension::DeathTime { token_id } => {
+                to_json_binary(&query::query_death_time(deps, token_id)?)
+            }
+            MagotchiQueryExtension::IsHatched { token_id } => {
+                to_json_binary(&query::query_is_hatched(deps, token_id)?)
+            }
+            MagotchiQueryExtension::GotchiState { token_id } => {
+                to_json_binary(&query::query_gotchi_state(deps, token_id)?)
+            }
+            MagotchiQueryExtension::TopCaretakers {
+                token_id,
+                start_after,
+                limit,
+            } => to_json_binary(&query::query_top_caretakers(
+                deps,
+                token_id,
+                start_after,
+                limit,
+            )?),
+            MagotchiQueryExtension::FeedingAllowlist {
+                token_id,
+                start_after,
+                limit,
+            } => to_json_binary(&query::query_feeding_allowlist(
+                deps,
+                token_id,
+                start_after,
+                limit,
+            )?),
+            MagotchiQueryExtension::GravesByOwner {
+                owner,
+                start_after,
+                limit,
+            } => to_json_binary(&query::query_graves_by_owner(
+                deps,
+                owner,
+                start_after,
+                limit,
+            )?),
+            MagotchiQueryExtension::Graves { start_after, limit } => {
+                to_json_binary(&query::query_graves(deps, start_after, limit)?)
+            }
+            MagotchiQueryExtension::Inventory {
+                owner,
+                start_after,
+                limit,
+            } => to_json_binary(&query::query_inventory(deps, owner, start_after, limit)?),
+            MagotchiQueryExtension::ItemCatalog {} => {
+                to_json_binary(&query::query_item_catalog(deps)?)
+            }
+            MagotchiQueryExtension::Wellbeing { token_id } => {
+                to_json_binary(&query::query_wellbeing(deps, env, token_id)?)
+            }
+            MagotchiQueryExtension::VacationStatus { token_id } => {
+                to_json_binary(&query::query_vacation_status(deps, env, token_id)?)
+            }
+            MagotchiQueryExtension::PauseStatus {} => {
+                to_json_binary(&query::query_pause_status(deps)?)
+            }
+            MagotchiQueryExtension::Treasury {} => to_json_binary(&query::query_treasury(deps)?),
+            MagotchiQueryExtension::RoyaltyInfo {
+                token_id,
+                sale_price,
+            } => to_json_binary(&query::query_royalty_info(deps, token_id, sale_price)?),
+            MagotchiQueryExtension::CheckRoyalties {} => {
+                to_json_binary(&query::query_check_royalties(deps)?)
+            }
+            MagotchiQueryExtension::Listing { token_id } => {
+                to_json_binary(&query::query_listing(deps, env, token_id)?)
+            }
+            MagotchiQueryExtension::ListingsBySeller {
+                seller,
+                start_after,
+                limit,
+            } => to_json_binary(&query::query_listings_by_seller(
+                deps,
+                env,
+                seller,
+                start_after,
+                limit,
+            )?),
+            MagotchiQueryExtension::ListingsByPrice {
+                denom,
+                start_after,
+                limit,
+            } => to_json_binary(&query::query_listings_by_price(
+                deps,
+                env,
+                denom,
+                start_after,
+                limit,
+            )?),
+            MagotchiQueryExtension::Listings {
+                status,
+                start_after,
+                limit,
+            } => to_json_binary(&query::query_listings(
+                deps,
+                env,
+                status,
+                start_after,
+                limit,
+            )?),
+            MagotchiQueryExtension::Auction { token_id } => {
+                to_json_binary(&query::query_auction(deps, token_id)?)
+            }
+            MagotchiQueryExtension::Auctions { start_after, limit } => {
+                to_json_binary(&query::query_auctions(deps, start_after, limit)?)
+            }
+            MagotchiQueryExtension::Rental { token_id } => {
+                to_json_binary(&query::query_rental(deps, token_id)?)
+            }
+            MagotchiQueryExtension::UserOf { token_id } => {
+                to_json_binary(&query::query_user_of(deps, env, token_id)?)
+            }
+            MagotchiQueryExtension::RandomnessRound { token_id } => {
+                to_json_binary(&query::query_randomness_round(deps, token_id)?)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -190,6 +545,48 @@ mod tests {
         assert_ne!(version.contract, cw721_base::CONTRACT_NAME);
     }
 
+    #[test]
+    fn batch_mint_initializes_live_states() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(CREATOR, &[]);
+        entry::instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                name: "Magotchi".into(),
+                symbol: "MAG".into(),
+                minter: None,
+                withdraw_address: None,
+            },
+        )
+        .unwrap();
+
+        let mints = ["egg1", "egg2"]
+            .iter()
+            .map(|token_id| cw721_base::MintMsg {
+                token_id: token_id.to_string(),
+                owner: "john".to_string(),
+                token_uri: None,
+                extension: None,
+            })
+            .collect();
+        entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::BatchMint { mints },
+        )
+        .unwrap();
+
+        for token_id in ["egg1", "egg2"] {
+            let state = state::LIVE_STATES
+                .load(deps.as_ref().storage, token_id.to_string())
+                .unwrap();
+            assert!(!state.is_hatched());
+        }
+    }
+
     #[test]
     fn use_metadata_extension() {
         let mut deps = mock_dependencies();
@@ -0,0 +1,127 @@
+use sha2::{Digest, Sha256};
+
+/// Returns the leaf of an address in the presale merkle tree
+pub fn leaf_hash(address: &str) -> [u8; 32] {
+    Sha256::digest(address.as_bytes()).into()
+}
+
+/// Hashes two nodes of the tree. The nodes are sorted first, so proofs don't need to know on
+/// which side of the path their siblings are
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    Sha256::new()
+        .chain_update(first)
+        .chain_update(second)
+        .finalize()
+        .into()
+}
+
+/// Decodes a hex encoded hash, returning None if it is not a valid 32 byte hash
+pub fn decode_hash(hash: &str) -> Option<[u8; 32]> {
+    hex::decode(hash).ok()?.try_into().ok()
+}
+
+/// Checks that the address is part of the tree with the given hex encoded root, with a proof of
+/// hex encoded sibling hashes from the leaf up to the root
+pub fn verify_proof(root: &str, address: &str, proof: &[String]) -> bool {
+    let Some(root) = decode_hash(root) else {
+        return false;
+    };
+
+    let mut hash = leaf_hash(address);
+    for sibling in proof {
+        match decode_hash(sibling) {
+            Some(sibling) => hash = hash_pair(&hash, &sibling),
+            None => return false,
+        }
+    }
+    hash == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use speculoos::prelude::*;
+
+    /// Builds all layers of a tree, from the leaves up to the root. A node without a sibling is
+    /// moved up to the next layer as is
+    fn build_tree(addresses: &[String]) -> Vec<Vec<[u8; 32]>> {
+        let mut layers = vec![addresses
+            .iter()
+            .map(|address| leaf_hash(address))
+            .collect::<Vec<_>>()];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        layers
+    }
+
+    fn root(layers: &[Vec<[u8; 32]>]) -> String {
+        hex::encode(layers.last().unwrap()[0])
+    }
+
+    fn proof(layers: &[Vec<[u8; 32]>], mut index: usize) -> Vec<String> {
+        let mut proof = vec![];
+        for layer in &layers[..layers.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < layer.len() {
+                proof.push(hex::encode(layer[sibling]));
+            }
+            index /= 2;
+        }
+        proof
+    }
+
+    fn addresses(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("neutron1address{i}")).collect()
+    }
+
+    #[test]
+    fn test_verify_generated_trees() {
+        for count in [1, 2, 3, 7, 8, 13] {
+            let addresses = addresses(count);
+            let layers = build_tree(&addresses);
+            let root = root(&layers);
+
+            for (index, address) in addresses.iter().enumerate() {
+                assert_that!(verify_proof(&root, address, &proof(&layers, index))).is_true();
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_invalid_proofs() {
+        let addresses = addresses(8);
+        let layers = build_tree(&addresses);
+        let root = root(&layers);
+
+        // not in the tree
+        assert_that!(verify_proof(&root, "neutron1outsider", &proof(&layers, 0))).is_false();
+        // the proof of another address
+        assert_that!(verify_proof(&root, &addresses[0], &proof(&layers, 1))).is_false();
+        // a truncated proof
+        let mut truncated = proof(&layers, 0);
+        truncated.pop();
+        assert_that!(verify_proof(&root, &addresses[0], &truncated)).is_false();
+        // invalid hex
+        assert_that!(verify_proof(&root, &addresses[0], &["zz".to_string()])).is_false();
+        assert_that!(verify_proof("root", &addresses[0], &proof(&layers, 0))).is_false();
+    }
+
+    #[test]
+    fn test_hash_pair_is_sorted() {
+        let a = leaf_hash("a");
+        let b = leaf_hash("b");
+        assert_that!(hash_pair(&a, &b)).is_equal_to(hash_pair(&b, &a));
+    }
+}
@@ -1,8 +1,18 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{CustomMsg, Timestamp, Uint128};
+use cosmwasm_std::{Coin, CustomMsg, Timestamp, Uint128};
+use cw721::Expiration;
 use cw_orch::{ExecuteFns, QueryFns};
 
-use crate::state::{Config, Gotchi, PartialConfig};
+use cw2981_royalties::{CheckRoyaltiesResponse, RoyaltiesInfoResponse};
+
+use crate::{
+    randomness::Round,
+    state::{
+        Auction, Config, Gotchi, ItemConfig, ListingStatus, PartialConfig, Pause, Rental,
+        RoyaltyConfig,
+    },
+    Metadata,
+};
 
 #[cw_serde]
 #[derive(ExecuteFns)]
@@ -15,6 +25,87 @@ pub enum MagotchiExecuteExtension {
     Reap { tokens: Option<Vec<String>> },
     /// Set the Config of the contract, including the daily feeding cost, the maximum days without food and the day length
     UpdateConfig { config: PartialConfig },
+    /// Allow an address to feed the magotchi when the allowlist feeding policy is active. Only the owner or an operator can call this
+    AllowFeeder { token_id: String, feeder: String },
+    /// Remove an address from the feeding allowlist of the magotchi. Only the owner or an operator can call this
+    DisallowFeeder { token_id: String, feeder: String },
+    /// Revive a dead magotchi by paying the revival cost. Only the current owner of the token can call this, which is the graveyard once the magotchi has been reaped
+    Revive { token_id: String },
+    /// Play with the magotchi, restoring its happiness. Only the owner or the borrower can call this
+    Play { token_id: String },
+    /// Clean the magotchi, restoring its hygiene. Only the owner or the borrower can call this
+    Clean { token_id: String },
+    /// Put the magotchi to sleep, restoring its energy. Only the owner or the borrower can call this
+    Sleep { token_id: String },
+    /// Buy items from the catalog into the inventory of the sender, the price of all items must be sent
+    BuyItem { item: String, amount: u32 },
+    /// Use an item from the inventory of the sender on the magotchi. Food and medicine can be used by anyone who can feed the magotchi, revive potions only by its owner
+    Use { token_id: String, item: String },
+    /// Buy a vacation for the magotchi, pausing its hunger for the given number of days. Only the owner or the borrower can call this
+    StartVacation { token_id: String, days: u32 },
+    /// Pause the contract in case of an emergency, blocking feeding, hatching, reaping and transfers and freezing the death clock of every magotchi. Only the contract owner can call this
+    Pause {},
+    /// Unpause the contract, the death times of the magotchis are shifted by the paused duration. Only the contract owner can call this
+    Unpause {},
+    /// Breed two adult magotchis, minting an unhatched egg to the sender. The sender must own or be approved for both parents and pay the breeding fee
+    Breed { parent_a: String, parent_b: String },
+    /// Mint a new magotchi to the sender for the public mint price
+    PublicMint {},
+    /// Mint a new magotchi during the presale. The proof contains the hex encoded sibling hashes from the leaf of the sender up to the merkle root
+    PresaleMint { proof: Vec<String> },
+    /// Commit to the metadata of a batch of mystery boxes. The commitment is the hex encoded sha256 hash of the json of a RevealBatch. Only the contract owner can call this
+    CommitReveal { commitment: String },
+    /// Reveal the metadata of a batch of mystery boxes, which must match a commitment. Anyone can call this
+    Reveal {
+        token_ids: Vec<String>,
+        metadata: Vec<Metadata>,
+    },
+    /// Set the royalties of a single magotchi, overriding the royalties of the config. Removes the override if no royalties are given. Only the contract owner can call this
+    SetTokenRoyalties {
+        token_id: String,
+        royalties: Option<RoyaltyConfig>,
+    },
+    /// List the magotchi for sale at the given price, approving the contract to transfer it. Listing it again updates the price. Only the owner can call this
+    List {
+        token_id: String,
+        price: Coin,
+        expires: Option<Expiration>,
+    },
+    /// Remove the magotchi from sale, revoking the approval of the contract. Only the owner can call this
+    Delist { token_id: String },
+    /// Buy a listed magotchi, the exact price must be sent. The royalties and the protocol fee are taken from the price and the rest is sent to the seller
+    Buy { token_id: String },
+    /// Start an english auction for the magotchi, which is held by the contract until the auction is settled. The duration is in seconds. Only the owner can call this
+    StartAuction {
+        token_id: String,
+        reserve_price: Coin,
+        min_increment: Uint128,
+        duration: u64,
+    },
+    /// Bid on a magotchi in auction, the bid must be sent and at least the reserve price or the highest bid plus the minimum increment. The previous highest bidder is refunded
+    Bid { token_id: String },
+    /// Settle an ended auction, sending the magotchi to the highest bidder and paying out the bid, or returning the magotchi to the seller if there were no bids. Anyone can call this
+    SettleAuction { token_id: String },
+    /// Cancel an auction without bids, returning the magotchi to the seller. Only the seller can call this
+    CancelAuction { token_id: String },
+    /// Lend the magotchi to the borrower until the given expiration. The borrower can feed and care for the magotchi, only the owner can transfer it. With a price, the rental starts once the borrower paid it. Only the owner can call this
+    Lend {
+        token_id: String,
+        to: String,
+        until: Expiration,
+        price: Option<Coin>,
+    },
+    /// Start a rental by paying its price to the owner. Only the borrower can call this
+    Rent { token_id: String },
+    /// End a rental. The borrower can end it at any time, the owner only once it expired or if it was never paid
+    EndRental { token_id: String },
+    /// Commit to a secret for the randomness round of an unhatched magotchi, whose seed drives its genes. The commitment is the hex encoded sha256 hash of the secret, and the bond of the config must be sent. Anyone can call this
+    CommitRandomness {
+        token_id: String,
+        commitment: String,
+    },
+    /// Reveal the secret of a commitment once the commit phase is over, refunding the bond. Only the player who committed can call this
+    RevealRandomness { token_id: String, secret: String },
 }
 
 impl CustomMsg for MagotchiExecuteExtension {}
@@ -46,6 +137,129 @@ pub enum MagotchiQueryExtension {
     /// Return the live state of the magotchi
     #[returns(Gotchi)]
     GotchiState { token_id: String },
+
+    /// Return the caretakers that contributed the most to feeding the magotchi, in order of their
+    /// contribution
+    #[returns(CaretakersResponse)]
+    TopCaretakers {
+        token_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the addresses on the feeding allowlist of the magotchi
+    #[returns(FeedersResponse)]
+    FeedingAllowlist {
+        token_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the graves of the magotchis that were reaped from the given owner
+    #[returns(GravesResponse)]
+    GravesByOwner {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the graves of all reaped magotchis, ordered by their time of death. start_after is a token_id
+    #[returns(GravesResponse)]
+    Graves {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the secondary stats of the magotchi and its overall wellbeing, from 0 to 100
+    #[returns(WellbeingResponse)]
+    Wellbeing { token_id: String },
+
+    /// Return the items in the inventory of the owner
+    #[returns(InventoryResponse)]
+    Inventory {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the catalog of items that can be bought
+    #[returns(ItemCatalogResponse)]
+    ItemCatalog {},
+
+    /// Return whether the magotchi is on vacation and how many vacation days are left this year
+    #[returns(VacationStatusResponse)]
+    VacationStatus { token_id: String },
+
+    /// Return whether the contract is paused, and the past pauses
+    #[returns(Pause)]
+    PauseStatus {},
+
+    /// Return the total revenue and the accrued but unwithdrawn revenue per recipient
+    #[returns(TreasuryResponse)]
+    Treasury {},
+
+    /// CW2981: Return the address to pay royalties to and the royalty amount for a sale of the magotchi at the given price
+    #[returns(RoyaltiesInfoResponse)]
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+
+    /// CW2981: Return whether the collection has royalties
+    #[returns(CheckRoyaltiesResponse)]
+    CheckRoyalties {},
+
+    /// Return the listing of the magotchi
+    #[returns(ListingResponse)]
+    Listing { token_id: String },
+
+    /// Return the listings of the given seller
+    #[returns(ListingsResponse)]
+    ListingsBySeller {
+        seller: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the listings in the given denom, ordered by their price. start_after is a token_id
+    #[returns(ListingsResponse)]
+    ListingsByPrice {
+        denom: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return a page of all listings, optionally only those with the given status. The limit
+    /// bounds the listings looked at, so a filtered page can hold fewer
+    #[returns(ListingsResponse)]
+    Listings {
+        status: Option<ListingStatus>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the auction of the magotchi
+    #[returns(Auction)]
+    Auction { token_id: String },
+
+    /// Return all auctions, ordered by token_id
+    #[returns(AuctionsResponse)]
+    Auctions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the rental of the magotchi, including unpaid and expired ones
+    #[returns(Rental)]
+    Rental { token_id: String },
+
+    /// Return the borrower of the magotchi and when the rental expires, if it is rented out
+    #[returns(UserResponse)]
+    UserOf { token_id: String },
+
+    /// Return the randomness round of an unhatched magotchi
+    #[returns(Round)]
+    RandomnessRound { token_id: String },
 }
 
 impl Default for MagotchiQueryExtension {
@@ -61,9 +275,121 @@ impl CustomMsg for MagotchiQueryExtension {}
 #[cw_serde]
 pub struct HealthResponse {
     pub health: u8,
+    /// a sick magotchi loses health faster until it is cured with medicine
+    pub sick: bool,
+}
+
+#[cw_serde]
+pub struct WellbeingResponse {
+    pub happiness: u32,
+    pub hygiene: u32,
+    pub energy: u32,
+    /// the average of the health in percent and the secondary stats
+    pub wellbeing: u32,
+}
+
+#[cw_serde]
+pub struct InventoryItem {
+    pub item: String,
+    pub amount: u32,
+}
+
+#[cw_serde]
+pub struct InventoryResponse {
+    pub items: Vec<InventoryItem>,
+}
+
+#[cw_serde]
+pub struct ItemCatalogResponse {
+    pub items: Vec<ItemConfig>,
 }
 
 #[cw_serde]
 pub struct FeedingCostResponse {
     pub cost: Uint128,
 }
+
+#[cw_serde]
+pub struct CaretakerResponse {
+    pub address: String,
+    pub feedings: u64,
+    pub contribution: u64,
+}
+
+#[cw_serde]
+pub struct CaretakersResponse {
+    pub caretakers: Vec<CaretakerResponse>,
+}
+
+#[cw_serde]
+pub struct FeedersResponse {
+    pub feeders: Vec<String>,
+}
+
+#[cw_serde]
+pub struct GraveResponse {
+    pub token_id: String,
+    pub former_owner: String,
+    pub died_at: Timestamp,
+    pub reaped_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct GravesResponse {
+    pub graves: Vec<GraveResponse>,
+}
+
+#[cw_serde]
+pub struct ListingResponse {
+    pub token_id: String,
+    pub seller: String,
+    pub price: Coin,
+    pub expires: Expiration,
+    pub status: ListingStatus,
+}
+
+#[cw_serde]
+pub struct ListingsResponse {
+    pub listings: Vec<ListingResponse>,
+    /// the last listing of the page, to continue from with start_after. With a status filter, it
+    /// may not match the status, so it isn't always part of the listings
+    pub last_token_id: Option<String>,
+}
+
+#[cw_serde]
+pub struct AuctionResponse {
+    pub token_id: String,
+    pub auction: Auction,
+}
+
+#[cw_serde]
+pub struct AuctionsResponse {
+    pub auctions: Vec<AuctionResponse>,
+}
+
+#[cw_serde]
+pub struct UserResponse {
+    pub user: Option<String>,
+    pub expires: Option<Expiration>,
+}
+
+#[cw_serde]
+pub struct VacationStatusResponse {
+    pub on_vacation: bool,
+    pub paused_until: Option<Timestamp>,
+    pub days_used_this_year: u32,
+    pub days_left_this_year: u32,
+}
+
+#[cw_serde]
+pub struct AccruedResponse {
+    /// the address of the recipient, or "reapers" for the reaper rewards
+    pub recipient: String,
+    pub amounts: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct TreasuryResponse {
+    pub revenue: Vec<Coin>,
+    pub accrued: Vec<AccruedResponse>,
+}
@@ -0,0 +1,281 @@
+use cosmwasm_std::{coins, Addr, Coin, Empty};
+use cw721::NftInfoResponse;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use speculoos::prelude::*;
+
+use crate::{
+    error::ContractError,
+    genetics::genome_from_dna,
+    msg::{MagotchiExecuteExtension, MagotchiQueryExtension},
+    randomness::{combine, commitment, Round},
+    state::{GeneticsConfig, PartialConfig, RandomnessConfig, TraitTable, WeightedTrait},
+    ExecuteMsg, Extension, InstantiateMsg, QueryMsg, Trait,
+};
+
+const CREATOR: &str = "creator";
+const ALICE: &str = "alice";
+const BOB: &str = "bob";
+const TOKEN_ID: &str = "egg1";
+
+fn cw721_gotchi_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        crate::entry::execute,
+        crate::entry::instantiate,
+        crate::entry::query,
+    );
+    Box::new(contract)
+}
+
+fn genetics() -> GeneticsConfig {
+    let option = |value: &str| WeightedTrait {
+        value: value.to_string(),
+        weight: 1,
+        hunger_multiplier: None,
+    };
+    GeneticsConfig {
+        traits: vec![
+            TraitTable {
+                trait_type: "color".to_string(),
+                options: vec![option("red"), option("blue"), option("green")],
+            },
+            TraitTable {
+                trait_type: "species".to_string(),
+                options: vec![option("newt"), option("axolotl")],
+            },
+        ],
+    }
+}
+
+/// Instantiates the contract with genetics and randomness, and mints an egg to alice
+fn setup() -> (App, Addr) {
+    let mut app = App::new(|router, _, storage| {
+        for player in [ALICE, BOB] {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(player), coins(1000, "uluna"))
+                .unwrap();
+        }
+    });
+    let code_id = app.store_code(cw721_gotchi_contract());
+    let gotchi = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(CREATOR),
+            &InstantiateMsg {
+                name: "Magotchi".to_string(),
+                symbol: "MAG".to_string(),
+                minter: None,
+                withdraw_address: None,
+            },
+            &[],
+            "magotchi",
+            None,
+        )
+        .unwrap();
+
+    let config = PartialConfig {
+        genetics: Some(Some(genetics())),
+        randomness: Some(Some(RandomnessConfig {
+            commit_seconds: 60,
+            reveal_seconds: 60,
+            bond: coins(100, "uluna"),
+            min_players: 1,
+        })),
+        ..Default::default()
+    };
+    execute(
+        &mut app,
+        &gotchi,
+        CREATOR,
+        MagotchiExecuteExtension::UpdateConfig { config },
+        0,
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(CREATOR),
+        gotchi.clone(),
+        &ExecuteMsg::Mint {
+            token_id: TOKEN_ID.to_string(),
+            owner: ALICE.to_string(),
+            token_uri: None,
+            extension: None,
+        },
+        &[],
+    )
+    .unwrap();
+    (app, gotchi)
+}
+
+fn execute(
+    app: &mut App,
+    gotchi: &Addr,
+    sender: &str,
+    msg: MagotchiExecuteExtension,
+    bond: u128,
+) -> Result<(), ContractError> {
+    let funds = match bond {
+        0 => vec![],
+        bond => coins(bond, "uluna"),
+    };
+    app.execute_contract(
+        Addr::unchecked(sender),
+        gotchi.clone(),
+        &ExecuteMsg::Extension { msg },
+        &funds,
+    )
+    .map(|_| ())
+    .map_err(|err| err.downcast().unwrap())
+}
+
+fn commit(app: &mut App, gotchi: &Addr, player: &str, secret: &str) -> Result<(), ContractError> {
+    let msg = MagotchiExecuteExtension::CommitRandomness {
+        token_id: TOKEN_ID.to_string(),
+        commitment: commitment(secret),
+    };
+    execute(app, gotchi, player, msg, 100)
+}
+
+fn reveal(app: &mut App, gotchi: &Addr, player: &str, secret: &str) -> Result<(), ContractError> {
+    let msg = MagotchiExecuteExtension::RevealRandomness {
+        token_id: TOKEN_ID.to_string(),
+        secret: secret.to_string(),
+    };
+    execute(app, gotchi, player, msg, 0)
+}
+
+fn hatch(app: &mut App, gotchi: &Addr) -> Result<(), ContractError> {
+    let msg = MagotchiExecuteExtension::Hatch {
+        token_id: TOKEN_ID.to_string(),
+    };
+    execute(app, gotchi, CREATOR, msg, 0)
+}
+
+fn advance(app: &mut App, seconds: u64) {
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(seconds);
+        block.height += seconds / 5;
+    });
+}
+
+fn traits(app: &App, gotchi: &Addr) -> Vec<Trait> {
+    let info: NftInfoResponse<Extension> = app
+        .wrap()
+        .query_wasm_smart(
+            gotchi,
+            &QueryMsg::NftInfo {
+                token_id: TOKEN_ID.to_string(),
+            },
+        )
+        .unwrap();
+    info.extension.unwrap().attributes.unwrap()
+}
+
+/// The funds of the contract that are not reserved, e.g. for bonds
+fn withdrawable(app: &App, gotchi: &Addr) -> u128 {
+    let funds: Coin = app
+        .wrap()
+        .query_wasm_smart(
+            gotchi,
+            &QueryMsg::WithdrawableFunds {
+                denom: "uluna".to_string(),
+            },
+        )
+        .unwrap();
+    funds.amount.u128()
+}
+
+fn balance(app: &App, player: &str) -> u128 {
+    app.wrap()
+        .query_balance(player, "uluna")
+        .unwrap()
+        .amount
+        .u128()
+}
+
+#[test]
+fn hatch_with_commit_reveal_randomness() {
+    let (mut app, gotchi) = setup();
+    assert_that!(hatch(&mut app, &gotchi)).is_err().is_equal_to(
+        ContractError::RandomnessNotReady {
+            token_id: TOKEN_ID.to_string(),
+        },
+    );
+
+    commit(&mut app, &gotchi, ALICE, "alice's secret").unwrap();
+    commit(&mut app, &gotchi, BOB, "bob's secret").unwrap();
+    assert_that!(reveal(&mut app, &gotchi, ALICE, "alice's secret"))
+        .is_err()
+        .is_equal_to(ContractError::RevealPhaseNotStarted {});
+    assert_that!(withdrawable(&app, &gotchi)).is_equal_to(0);
+
+    advance(&mut app, 60);
+    reveal(&mut app, &gotchi, ALICE, "alice's secret").unwrap();
+    assert_that!(balance(&app, ALICE)).is_equal_to(1000);
+    assert_that!(hatch(&mut app, &gotchi)).is_err().is_equal_to(
+        ContractError::RandomnessNotReady {
+            token_id: TOKEN_ID.to_string(),
+        },
+    );
+
+    // bob never reveals, so his secret is left out and his bond is kept
+    advance(&mut app, 60);
+    hatch(&mut app, &gotchi).unwrap();
+    let genome = genome_from_dna(&genetics(), combine(TOKEN_ID, &["alice's secret"]));
+    assert_that!(traits(&app, &gotchi)).is_equal_to(genome.traits);
+    assert_that!(balance(&app, BOB)).is_equal_to(900);
+    assert_that!(withdrawable(&app, &gotchi)).is_equal_to(100);
+
+    let round: Result<Round, _> = app.wrap().query_wasm_smart(
+        &gotchi,
+        &QueryMsg::Extension {
+            msg: MagotchiQueryExtension::RandomnessRound {
+                token_id: TOKEN_ID.to_string(),
+            },
+        },
+    );
+    assert_that!(round).is_err();
+}
+
+#[test]
+fn genes_do_not_depend_on_the_block() {
+    let hatch_after = |seconds| {
+        let (mut app, gotchi) = setup();
+        advance(&mut app, seconds);
+        commit(&mut app, &gotchi, ALICE, "alice's secret").unwrap();
+        commit(&mut app, &gotchi, BOB, "bob's secret").unwrap();
+        advance(&mut app, 60);
+        reveal(&mut app, &gotchi, BOB, "bob's secret").unwrap();
+        reveal(&mut app, &gotchi, ALICE, "alice's secret").unwrap();
+        advance(&mut app, seconds);
+        hatch(&mut app, &gotchi).unwrap();
+        traits(&app, &gotchi)
+    };
+
+    let genome = genome_from_dna(
+        &genetics(),
+        combine(TOKEN_ID, &["alice's secret", "bob's secret"]),
+    );
+    assert_that!(hatch_after(0)).is_equal_to(genome.traits.clone());
+    assert_that!(hatch_after(3600)).is_equal_to(genome.traits);
+}
+
+#[test]
+fn expired_rounds_start_over() {
+    let (mut app, gotchi) = setup();
+    commit(&mut app, &gotchi, BOB, "bob's secret").unwrap();
+
+    // nobody revealed, so the next commitment starts a new round
+    advance(&mut app, 120);
+    assert_that!(hatch(&mut app, &gotchi)).is_err();
+    commit(&mut app, &gotchi, ALICE, "alice's secret").unwrap();
+    advance(&mut app, 60);
+    assert_that!(reveal(&mut app, &gotchi, BOB, "bob's secret"))
+        .is_err()
+        .is_equal_to(ContractError::NotCommitted {});
+    reveal(&mut app, &gotchi, ALICE, "alice's secret").unwrap();
+    hatch(&mut app, &gotchi).unwrap();
+
+    let genome = genome_from_dna(&genetics(), combine(TOKEN_ID, &["alice's secret"]));
+    assert_that!(traits(&app, &gotchi)).is_equal_to(genome.traits);
+    assert_that!(balance(&app, BOB)).is_equal_to(900);
+}
@@ -1,20 +1,91 @@
-use cosmwasm_std::{Deps, Env, StdResult, Timestamp};
+use cosmwasm_std::{Addr, Coin, Deps, Env, Order, StdResult, Timestamp, Uint128};
+use cw2981_royalties::{CheckRoyaltiesResponse, RoyaltiesInfoResponse};
+use cw721::{AllNftInfoResponse, Cw721Query, NftInfoResponse};
+use cw_storage_plus::Bound;
+use cw_utils::maybe_addr;
 
 use crate::{
-    msg::HealthResponse,
-    state::{Gotchi, CONFIG, LIVE_STATES},
+    genetics::merge_traits,
+    msg::{
+        AccruedResponse, AuctionResponse, AuctionsResponse, CaretakerResponse, CaretakersResponse,
+        FeedersResponse, GraveResponse, GravesResponse, HealthResponse, InventoryItem,
+        InventoryResponse, ItemCatalogResponse, ListingResponse, ListingsResponse,
+        TreasuryResponse, UserResponse, VacationStatusResponse, WellbeingResponse,
+    },
+    randomness::Round,
+    state::{
+        graves, listings, load_gotchi, Auction, CareAction, Gotchi, Grave, Listing, ListingStatus,
+        Pause, Rental, RoyaltyConfig, ACCRUED, AUCTIONS, CARETAKERS, CONFIG, FEEDING_ALLOWLIST,
+        INVENTORIES, LIVE_STATES, MAX_STAT, PAUSE, RANDOMNESS_ROUNDS, RENTALS, REVENUE,
+        TOKEN_ROYALTIES, TOP_CARETAKERS, UNREVEALED,
+    },
+    Cw721MetadataContract, Extension, Trait,
 };
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
 pub fn query_health(deps: Deps, env: Env, token_id: String) -> StdResult<HealthResponse> {
-    let state = LIVE_STATES.load(deps.storage, token_id.clone())?;
+    let state = load_gotchi(deps.storage, &token_id)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let health = state.health(&env.block, config.max_unfed_days_for(&state)) as u8;
+    Ok(HealthResponse {
+        health,
+        sick: state.is_sick(),
+    })
+}
+
+pub fn query_wellbeing(deps: Deps, env: Env, token_id: String) -> StdResult<WellbeingResponse> {
+    let state = load_gotchi(deps.storage, &token_id)?;
     let config = CONFIG.load(deps.storage)?;
+    let care = config.care.clone().unwrap_or_default();
+    let stat = |action| state.stat(&env.block, &action, care.get(&action));
+
+    let max_unfed_days = config.max_unfed_days_for(&state);
+    let health = state.health(&env.block, max_unfed_days) * MAX_STAT / max_unfed_days as u32;
+    let (happiness, hygiene, energy) = (
+        stat(CareAction::Play),
+        stat(CareAction::Clean),
+        stat(CareAction::Sleep),
+    );
+    Ok(WellbeingResponse {
+        happiness,
+        hygiene,
+        energy,
+        wellbeing: (health + happiness + hygiene + energy) / 4,
+    })
+}
+
+pub fn query_inventory(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<InventoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let owner = deps.api.addr_validate(&owner)?;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let items = INVENTORIES
+        .prefix(&owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| !matches!(item, Ok((_, 0))))
+        .take(limit)
+        .map(|item| item.map(|(item, amount)| InventoryItem { item, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(InventoryResponse { items })
+}
 
-    let health = state.health(&env.block, config.max_unfed_days as u64) as u8;
-    Ok(HealthResponse { health })
+pub fn query_item_catalog(deps: Deps) -> StdResult<ItemCatalogResponse> {
+    Ok(ItemCatalogResponse {
+        items: CONFIG.load(deps.storage)?.items,
+    })
 }
 
 pub fn query_hatched_at(deps: Deps, token_id: String) -> StdResult<Timestamp> {
-    let state = LIVE_STATES.load(deps.storage, token_id.clone())?;
+    let state = load_gotchi(deps.storage, &token_id)?;
     match state.hatched_at() {
         Some(hatched_at) => Ok(hatched_at),
         None => Err(cosmwasm_std::StdError::not_found("No birthday set")),
@@ -22,29 +93,434 @@ pub fn query_hatched_at(deps: Deps, token_id: String) -> StdResult<Timestamp> {
 }
 
 pub fn query_death_time(deps: Deps, token_id: String) -> StdResult<Timestamp> {
-    let state = LIVE_STATES.load(deps.storage, token_id.clone())?;
+    let state = load_gotchi(deps.storage, &token_id)?;
     Ok(state.death_time())
 }
 
 pub fn query_is_hatched(deps: Deps, token_id: String) -> StdResult<bool> {
-    let state = LIVE_STATES.load(deps.storage, token_id.clone())?;
+    let state = load_gotchi(deps.storage, &token_id)?;
     Ok(state.is_hatched())
 }
 
 pub fn query_is_alive(deps: Deps, token_id: String, env: Env) -> StdResult<bool> {
-    let state = LIVE_STATES.load(deps.storage, token_id.clone())?;
+    let state = load_gotchi(deps.storage, &token_id)?;
     Ok(!state.is_dead(&env.block))
 }
 
 pub fn query_feeding_cost(deps: Deps, env: Env, token_id: String) -> StdResult<u128> {
-    let state = LIVE_STATES.load(deps.storage, token_id.clone())?;
+    let state = load_gotchi(deps.storage, &token_id)?;
     let config = CONFIG.load(deps.storage)?;
     Ok(config.get_feeding_cost(&state, &env.block).into())
 }
 
 pub fn query_gotchi_state(deps: Deps, token_id: String) -> StdResult<Gotchi> {
-    LIVE_STATES.load(deps.storage, token_id.clone())
+    load_gotchi(deps.storage, &token_id)
 }
 pub fn query_config(deps: Deps) -> StdResult<crate::state::Config> {
     CONFIG.load(deps.storage)
 }
+
+pub fn query_top_caretakers(
+    deps: Deps,
+    token_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CaretakersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let top = TOP_CARETAKERS
+        .may_load(deps.storage, &token_id)?
+        .unwrap_or_default();
+    let start = match start_after {
+        Some(start_after) => top
+            .iter()
+            .position(|address| address.as_str() == start_after)
+            .map_or(top.len(), |index| index + 1),
+        None => 0,
+    };
+
+    let caretakers = top
+        .into_iter()
+        .skip(start)
+        .take(limit)
+        .map(|address| -> StdResult<_> {
+            let caretaker = CARETAKERS.load(deps.storage, (token_id.as_str(), &address))?;
+            Ok(CaretakerResponse {
+                address: address.into_string(),
+                feedings: caretaker.feedings,
+                contribution: caretaker.contribution,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CaretakersResponse { caretakers })
+}
+
+pub fn query_feeding_allowlist(
+    deps: Deps,
+    token_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FeedersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let feeders = FEEDING_ALLOWLIST
+        .prefix(&token_id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(Addr::into_string))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FeedersResponse { feeders })
+}
+
+pub fn query_graves_by_owner(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<GravesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let owner = deps.api.addr_validate(&owner)?;
+    let start = start_after.map(Bound::exclusive);
+
+    let graves = graves()
+        .idx
+        .former_owner
+        .prefix(owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(grave_response))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(GravesResponse { graves })
+}
+
+pub fn query_graves(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<GravesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = match start_after {
+        Some(token_id) => {
+            let grave = graves().load(deps.storage, token_id.clone())?;
+            Some(Bound::exclusive((grave.died_at.seconds(), token_id)))
+        }
+        None => None,
+    };
+
+    let graves = graves()
+        .idx
+        .died_at
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(grave_response))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(GravesResponse { graves })
+}
+
+fn grave_response((token_id, grave): (String, Grave)) -> GraveResponse {
+    GraveResponse {
+        token_id,
+        former_owner: grave.former_owner.into_string(),
+        died_at: grave.died_at,
+        reaped_at: grave.reaped_at,
+    }
+}
+
+pub fn query_listing(deps: Deps, env: Env, token_id: String) -> StdResult<ListingResponse> {
+    let listing = listings().load(deps.storage, &token_id)?;
+    listing_response(deps, &env, (token_id, listing))
+}
+
+pub fn query_listings_by_seller(
+    deps: Deps,
+    env: Env,
+    seller: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let seller = deps.api.addr_validate(&seller)?;
+    let start = start_after.map(Bound::exclusive);
+
+    let listings = listings()
+        .idx
+        .seller
+        .prefix(seller)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| listing_response(deps, &env, item?))
+        .collect::<StdResult<Vec<_>>>()?;
+    let last_token_id = listings.last().map(|listing| listing.token_id.clone());
+
+    Ok(ListingsResponse {
+        listings,
+        last_token_id,
+    })
+}
+
+pub fn query_listings_by_price(
+    deps: Deps,
+    env: Env,
+    denom: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = match start_after {
+        Some(token_id) => {
+            let listing = listings().load(deps.storage, &token_id)?;
+            Bound::exclusive(((listing.price.denom, listing.price.amount.u128()), token_id))
+        }
+        None => Bound::inclusive(((denom.clone(), 0u128), String::new())),
+    };
+
+    let listings = listings()
+        .idx
+        .price
+        .range(deps.storage, Some(start), None, Order::Ascending)
+        .take_while(|item| {
+            item.as_ref()
+                .map_or(true, |(_, listing)| listing.price.denom == denom)
+        })
+        .take(limit)
+        .map(|item| listing_response(deps, &env, item?))
+        .collect::<StdResult<Vec<_>>>()?;
+    let last_token_id = listings.last().map(|listing| listing.token_id.clone());
+
+    Ok(ListingsResponse {
+        listings,
+        last_token_id,
+    })
+}
+
+pub fn query_listings(
+    deps: Deps,
+    env: Env,
+    status: Option<ListingStatus>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    // the status is only known once a listing is loaded, so the limit applies before the filter
+    let page = listings()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| listing_response(deps, &env, item?))
+        .collect::<StdResult<Vec<_>>>()?;
+    let last_token_id = page.last().map(|listing| listing.token_id.clone());
+    let listings = page
+        .into_iter()
+        .filter(|listing| {
+            status
+                .as_ref()
+                .map_or(true, |status| &listing.status == status)
+        })
+        .collect();
+
+    Ok(ListingsResponse {
+        listings,
+        last_token_id,
+    })
+}
+
+fn listing_response(
+    deps: Deps,
+    env: &Env,
+    (token_id, listing): (String, Listing),
+) -> StdResult<ListingResponse> {
+    let state = load_gotchi(deps.storage, &token_id)?;
+
+    Ok(ListingResponse {
+        status: listing.status(&state, &env.block),
+        token_id,
+        seller: listing.seller.into_string(),
+        price: listing.price,
+        expires: listing.expires,
+    })
+}
+
+pub fn query_auction(deps: Deps, token_id: String) -> StdResult<Auction> {
+    let mut auction = AUCTIONS.load(deps.storage, &token_id)?;
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    auction.catch_up_pauses(&pause.periods);
+    Ok(auction)
+}
+
+pub fn query_auctions(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AuctionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+
+    let auctions = AUCTIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(token_id, mut auction)| {
+                auction.catch_up_pauses(&pause.periods);
+                AuctionResponse { token_id, auction }
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AuctionsResponse { auctions })
+}
+
+pub fn query_rental(deps: Deps, token_id: String) -> StdResult<Rental> {
+    RENTALS.load(deps.storage, &token_id)
+}
+
+pub fn query_randomness_round(deps: Deps, token_id: String) -> StdResult<Round> {
+    RANDOMNESS_ROUNDS.load(deps.storage, &token_id)
+}
+
+pub fn query_user_of(deps: Deps, env: Env, token_id: String) -> StdResult<UserResponse> {
+    Ok(match RENTALS.may_load(deps.storage, &token_id)? {
+        Some(rental) if rental.is_active(&env.block) => UserResponse {
+            user: Some(rental.user.spender.into_string()),
+            expires: Some(rental.user.expires),
+        },
+        _ => UserResponse {
+            user: None,
+            expires: None,
+        },
+    })
+}
+
+pub fn query_vacation_status(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+) -> StdResult<VacationStatusResponse> {
+    let state = load_gotchi(deps.storage, &token_id)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let days_used_this_year = state.vacation_days_used(&env.block);
+    let days_left_this_year = config.vacation.map_or(0, |vacation| {
+        vacation
+            .max_days_per_year
+            .saturating_sub(days_used_this_year)
+    });
+
+    Ok(VacationStatusResponse {
+        on_vacation: state.is_on_vacation(&env.block),
+        paused_until: state.paused_until(),
+        days_used_this_year,
+        days_left_this_year,
+    })
+}
+
+pub fn query_pause_status(deps: Deps) -> StdResult<Pause> {
+    Ok(PAUSE.may_load(deps.storage)?.unwrap_or_default())
+}
+
+pub fn query_treasury(deps: Deps) -> StdResult<TreasuryResponse> {
+    let revenue = REVENUE
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // the ledger is ordered by recipient, so the amounts of a recipient are next to each other
+    let mut accrued: Vec<AccruedResponse> = vec![];
+    for item in ACCRUED.range(deps.storage, None, None, Order::Ascending) {
+        let ((recipient, denom), amount) = item?;
+        let coin = Coin { denom, amount };
+        match accrued.last_mut() {
+            Some(last) if last.recipient == recipient => last.amounts.push(coin),
+            _ => accrued.push(AccruedResponse {
+                recipient,
+                amounts: vec![coin],
+            }),
+        }
+    }
+
+    Ok(TreasuryResponse { revenue, accrued })
+}
+
+pub fn query_royalty_info(
+    deps: Deps,
+    token_id: String,
+    sale_price: Uint128,
+) -> StdResult<RoyaltiesInfoResponse> {
+    Ok(match RoyaltyConfig::load(deps.storage, &token_id)? {
+        Some(royalties) => RoyaltiesInfoResponse {
+            address: royalties.payment_address.to_string(),
+            royalty_amount: royalties.amount(sale_price),
+        },
+        None => RoyaltiesInfoResponse {
+            address: String::new(),
+            royalty_amount: Uint128::zero(),
+        },
+    })
+}
+
+pub fn query_check_royalties(deps: Deps) -> StdResult<CheckRoyaltiesResponse> {
+    let royalty_payments =
+        CONFIG.load(deps.storage)?.royalties.is_some() || !TOKEN_ROYALTIES.is_empty(deps.storage);
+    Ok(CheckRoyaltiesResponse { royalty_payments })
+}
+
+/// Returns the metadata of a magotchi, or the placeholder if it is an unrevealed mystery box
+fn revealed_extension(deps: Deps, token_id: &str, extension: Extension) -> StdResult<Extension> {
+    if !UNREVEALED.has(deps.storage, token_id) {
+        return Ok(extension);
+    }
+    Ok(match CONFIG.load(deps.storage)?.reveal {
+        Some(reveal) => Some(reveal.placeholder),
+        None => extension,
+    })
+}
+
+/// Adds a status trait to the metadata of a sick magotchi, even if it was minted without any
+fn sickness_extension(deps: Deps, token_id: &str, extension: Extension) -> StdResult<Extension> {
+    let sick = LIVE_STATES
+        .may_load(deps.storage, token_id.to_string())?
+        .map_or(false, |state| state.is_sick());
+    Ok(match extension {
+        extension if sick => {
+            let mut metadata = extension.unwrap_or_default();
+            let status = Trait {
+                display_type: None,
+                trait_type: "status".to_string(),
+                value: "sick".to_string(),
+            };
+            metadata.attributes = Some(merge_traits(metadata.attributes, &[status]));
+            Some(metadata)
+        }
+        extension => extension,
+    })
+}
+
+pub fn query_nft_info(deps: Deps, token_id: String) -> StdResult<NftInfoResponse<Extension>> {
+    let mut info = Cw721MetadataContract::default().nft_info(deps, token_id.clone())?;
+    info.extension = revealed_extension(deps, &token_id, info.extension)?;
+    info.extension = sickness_extension(deps, &token_id, info.extension)?;
+    Ok(info)
+}
+
+pub fn query_all_nft_info(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<AllNftInfoResponse<Extension>> {
+    let mut res = Cw721MetadataContract::default().all_nft_info(
+        deps,
+        env,
+        token_id.clone(),
+        include_expired,
+    )?;
+    res.info.extension = revealed_extension(deps, &token_id, res.info.extension)?;
+    res.info.extension = sickness_extension(deps, &token_id, res.info.extension)?;
+    Ok(res)
+}
@@ -0,0 +1,321 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, BlockInfo, Coin, Timestamp};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{CResult, ContractError},
+    merkle::decode_hash,
+    state::RandomnessConfig,
+};
+
+/// The maximum number of players in a round, which keeps loading and saving a round cheap
+pub const MAX_PLAYERS: usize = 16;
+
+/// A commit-reveal round. Players commit to the hash of a secret until the commit deadline, and
+/// reveal their secret until the reveal deadline. The seed is derived from all revealed secrets,
+/// and block data is not involved at all. No player can predict the seed while the secrets of
+/// the others are unknown, but the last player to reveal can compute it and decide to withhold
+/// their secret instead, at the cost of their bond
+#[cw_serde]
+pub struct Round {
+    pub commit_deadline: Timestamp,
+    pub reveal_deadline: Timestamp,
+    /// the number of revealed secrets the round needs to produce a seed
+    pub min_players: u32,
+    pub entries: Vec<Entry>,
+}
+
+#[cw_serde]
+pub struct Entry {
+    pub player: Addr,
+    /// the hex encoded sha256 hash of the secret
+    pub commitment: String,
+    pub secret: Option<String>,
+    /// the bond paid with the commitment, refunded when the secret is revealed
+    pub bond: Option<Coin>,
+}
+
+impl Round {
+    pub fn new(block: &BlockInfo, config: &RandomnessConfig) -> Self {
+        let commit_deadline = block.time.plus_seconds(config.commit_seconds);
+        Self {
+            commit_deadline,
+            reveal_deadline: commit_deadline.plus_seconds(config.reveal_seconds),
+            min_players: config.min_players,
+            entries: vec![],
+        }
+    }
+
+    pub fn commit(
+        &mut self,
+        block: &BlockInfo,
+        player: &Addr,
+        commitment: &str,
+        bond: Option<Coin>,
+    ) -> CResult<()> {
+        if block.time >= self.commit_deadline {
+            return Err(ContractError::CommitPhaseOver {});
+        }
+        if decode_hash(commitment).is_none() {
+            return Err(ContractError::InvalidCommitment {});
+        }
+        if self.entries.iter().any(|entry| &entry.player == player) {
+            return Err(ContractError::AlreadyCommitted {});
+        }
+        if self.entries.len() >= MAX_PLAYERS {
+            return Err(ContractError::RoundFull { max: MAX_PLAYERS });
+        }
+
+        self.entries.push(Entry {
+            player: player.clone(),
+            commitment: commitment.to_lowercase(),
+            secret: None,
+            bond,
+        });
+        Ok(())
+    }
+
+    /// Reveals the secret of the player, returning the bond to refund
+    pub fn reveal(
+        &mut self,
+        block: &BlockInfo,
+        player: &Addr,
+        secret: &str,
+    ) -> CResult<Option<Coin>> {
+        if block.time < self.commit_deadline {
+            return Err(ContractError::RevealPhaseNotStarted {});
+        }
+        if block.time >= self.reveal_deadline {
+            return Err(ContractError::RevealPhaseOver {});
+        }
+
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|entry| &entry.player == player && entry.secret.is_none())
+            .ok_or(ContractError::NotCommitted {})?;
+        if entry.commitment != commitment(secret) {
+            return Err(ContractError::InvalidSecret {});
+        }
+
+        entry.secret = Some(secret.to_string());
+        Ok(entry.bond.take())
+    }
+
+    /// A round is complete once all players revealed their secret, or the reveal deadline passed
+    pub fn is_complete(&self, block: &BlockInfo) -> bool {
+        block.time >= self.reveal_deadline
+            || (block.time >= self.commit_deadline
+                && self.entries.iter().all(|entry| entry.secret.is_some()))
+    }
+
+    /// A complete round with fewer than `min_players` revealed secrets, or none at all, can't
+    /// produce a seed and is replaced by the next commitment
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        let revealed = self
+            .entries
+            .iter()
+            .filter(|entry| entry.secret.is_some())
+            .count();
+        self.is_complete(block) && revealed < self.min_players.max(1) as usize
+    }
+
+    /// Returns the seed of a complete round, combined from the revealed secrets in the order of
+    /// their commitments. Players that never revealed are left out
+    pub fn seed(&self, id: &str, block: &BlockInfo) -> Option<[u8; 32]> {
+        if !self.is_complete(block) || self.is_expired(block) {
+            return None;
+        }
+        let secrets: Vec<&str> = self
+            .entries
+            .iter()
+            .filter_map(|entry| entry.secret.as_deref())
+            .collect();
+        Some(combine(id, &secrets))
+    }
+
+    /// The bonds of the players that never revealed their secret
+    pub fn forfeited(&self) -> Vec<Coin> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.secret.is_none())
+            .filter_map(|entry| entry.bond.clone())
+            .collect()
+    }
+}
+
+/// Returns the hex encoded commitment to a secret
+pub fn commitment(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// Combines the secrets into a seed. The secrets are hashed before they are combined, so they
+/// can't be shifted into each other
+pub fn combine(id: &str, secrets: &[&str]) -> [u8; 32] {
+    secrets
+        .iter()
+        .fold(
+            Sha256::new().chain_update(id.as_bytes()),
+            |hasher, secret| hasher.chain_update(Sha256::digest(secret.as_bytes())),
+        )
+        .finalize()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_env;
+    use speculoos::prelude::*;
+
+    fn config() -> RandomnessConfig {
+        RandomnessConfig {
+            commit_seconds: 60,
+            reveal_seconds: 60,
+            bond: vec![],
+            min_players: 1,
+        }
+    }
+
+    fn block_plus(seconds: u64) -> BlockInfo {
+        let mut block = mock_env().block;
+        block.time = block.time.plus_seconds(seconds);
+        block
+    }
+
+    #[test]
+    fn commit_and_reveal() {
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        let mut round = Round::new(&block_plus(0), &config());
+
+        assert_that!(round.commit(&block_plus(0), &alice, "secret", None))
+            .is_err()
+            .is_equal_to(ContractError::InvalidCommitment {});
+        round
+            .commit(&block_plus(0), &alice, &commitment("alice"), None)
+            .unwrap();
+        assert_that!(round.commit(&block_plus(10), &alice, &commitment("alice"), None))
+            .is_err()
+            .is_equal_to(ContractError::AlreadyCommitted {});
+        round
+            .commit(
+                &block_plus(59),
+                &bob,
+                &commitment("bob"),
+                Some(Coin::new(100, "uluna")),
+            )
+            .unwrap();
+        assert_that!(round.commit(&block_plus(60), &bob, &commitment("bob"), None))
+            .is_err()
+            .is_equal_to(ContractError::CommitPhaseOver {});
+
+        assert_that!(round.reveal(&block_plus(59), &alice, "alice"))
+            .is_err()
+            .is_equal_to(ContractError::RevealPhaseNotStarted {});
+        assert_that!(round.reveal(&block_plus(60), &alice, "bob"))
+            .is_err()
+            .is_equal_to(ContractError::InvalidSecret {});
+        assert_that!(round.reveal(&block_plus(60), &alice, "alice"))
+            .is_ok()
+            .is_none();
+        assert_that!(round.reveal(&block_plus(60), &alice, "alice"))
+            .is_err()
+            .is_equal_to(ContractError::NotCommitted {});
+        assert_that!(round.seed("egg1", &block_plus(60))).is_none();
+
+        // the bond is refunded with the reveal
+        assert_that!(round.reveal(&block_plus(119), &bob, "bob"))
+            .is_ok()
+            .is_equal_to(Some(Coin::new(100, "uluna")));
+        assert_that!(round.seed("egg1", &block_plus(60)))
+            .is_equal_to(Some(combine("egg1", &["alice", "bob"])));
+        assert_that!(round.forfeited()).is_empty();
+    }
+
+    #[test]
+    fn timeout_leaves_out_unrevealed_secrets() {
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        let bond = Coin::new(100, "uluna");
+        let mut round = Round::new(&block_plus(0), &config());
+        round
+            .commit(&block_plus(0), &alice, &commitment("alice"), None)
+            .unwrap();
+        round
+            .commit(&block_plus(0), &bob, &commitment("bob"), Some(bond.clone()))
+            .unwrap();
+
+        assert_that!(round.seed("egg1", &block_plus(120))).is_none();
+        assert_that!(round.is_expired(&block_plus(120))).is_true();
+
+        round.reveal(&block_plus(60), &alice, "alice").unwrap();
+        assert_that!(round.seed("egg1", &block_plus(119))).is_none();
+        assert_that!(round.reveal(&block_plus(120), &bob, "bob"))
+            .is_err()
+            .is_equal_to(ContractError::RevealPhaseOver {});
+        assert_that!(round.seed("egg1", &block_plus(120)))
+            .is_equal_to(Some(combine("egg1", &["alice"])));
+        assert_that!(round.forfeited()).is_equal_to(vec![bond]);
+    }
+
+    #[test]
+    fn rounds_are_capped() {
+        let mut round = Round::new(&block_plus(0), &config());
+        for index in 0..MAX_PLAYERS {
+            let player = Addr::unchecked(format!("player{index}"));
+            round
+                .commit(&block_plus(0), &player, &commitment("secret"), None)
+                .unwrap();
+        }
+        assert_that!(round.commit(
+            &block_plus(0),
+            &Addr::unchecked("late"),
+            &commitment("secret"),
+            None
+        ))
+        .is_err()
+        .is_equal_to(ContractError::RoundFull { max: MAX_PLAYERS });
+    }
+
+    #[test]
+    fn rounds_need_min_players() {
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        let config = RandomnessConfig {
+            min_players: 2,
+            ..config()
+        };
+        let mut round = Round::new(&block_plus(0), &config);
+        round
+            .commit(&block_plus(0), &alice, &commitment("alice"), None)
+            .unwrap();
+
+        // a single player knows the seed, so the round can't complete
+        round.reveal(&block_plus(60), &alice, "alice").unwrap();
+        assert_that!(round.is_expired(&block_plus(60))).is_true();
+        assert_that!(round.seed("egg1", &block_plus(120))).is_none();
+
+        let mut round = Round::new(&block_plus(0), &config);
+        round
+            .commit(&block_plus(0), &alice, &commitment("alice"), None)
+            .unwrap();
+        round
+            .commit(&block_plus(0), &bob, &commitment("bob"), None)
+            .unwrap();
+        round.reveal(&block_plus(60), &alice, "alice").unwrap();
+        round.reveal(&block_plus(60), &bob, "bob").unwrap();
+        assert_that!(round.seed("egg1", &block_plus(60)))
+            .is_equal_to(Some(combine("egg1", &["alice", "bob"])));
+    }
+
+    #[test]
+    fn seeds_are_deterministic() {
+        assert_that!(combine("egg1", &["alice", "bob"]))
+            .is_equal_to(combine("egg1", &["alice", "bob"]));
+        assert_that!(combine("egg1", &["alice", "bob"]))
+            .is_not_equal_to(combine("egg2", &["alice", "bob"]));
+        assert_that!(combine("egg1", &["alic", "ebob"]))
+            .is_not_equal_to(combine("egg1", &["alice", "bob"]));
+    }
+}
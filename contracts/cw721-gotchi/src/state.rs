@@ -1,21 +1,374 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, BlockInfo, Coin, Timestamp, Uint128};
+use cosmwasm_std::{
+    to_json_vec, Addr, BlockInfo, Coin, Empty, StdResult, Storage, Timestamp, Uint128,
+};
 use cw721::Expiration;
-use cw_storage_plus::{Item, Map};
+use cw721_base::state::Approval;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 use partially::Partial;
+use sha2::{Digest, Sha256};
 
 use crate::{
     error::{CResult, ContractError},
+    merkle::decode_hash,
+    randomness::Round,
     utils::calculate_total_cost,
+    Metadata, Trait,
 };
 
+/// The length of a vacation year, used to cap the vacation days per magotchi
+const DAYS_PER_YEAR: u64 = 365;
+/// The value of a secondary stat right after its care action
+pub const MAX_STAT: u32 = 100;
+/// The highest hunger multiplier a genetic trait can have, in promille
+pub const MAX_HUNGER_MULTIPLIER: u64 = 10_000;
+
 pub const LIVE_STATES: Map<String, Gotchi> = Map::new("live_states");
 pub const CONFIG: Item<Config> = Item::new("config");
+/// The global emergency pause, freezing the death clock of every magotchi
+pub const PAUSE: Item<Pause> = Item::new("pause");
+/// The number of the next egg that is minted by breeding
+pub const NEXT_EGG_ID: Item<u64> = Item::new("next_egg_id");
+/// The number of the next token that is minted by the public mint
+pub const NEXT_TOKEN_ID: Item<u64> = Item::new("next_token_id");
+/// The number of tokens minted by the public mint. Unlike the token count, burns don't lower it
+pub const PUBLIC_SUPPLY: Item<u64> = Item::new("public_supply");
+/// The number of tokens minted in the presale
+pub const PRESALE_SUPPLY: Item<u64> = Item::new("presale_supply");
+/// The number of tokens every address minted in the public mint
+pub const PUBLIC_MINTED: Map<&Addr, u32> = Map::new("public_minted");
+/// The number of tokens every address minted in the presale
+pub const PRESALE_MINTED: Map<&Addr, u32> = Map::new("presale_minted");
+/// The total revenue per denom, since the treasury was configured
+pub const REVENUE: Map<&str, Uint128> = Map::new("revenue");
+/// Stored as (recipient key, denom), the revenue that was split to a recipient but not paid out yet
+pub const ACCRUED: Map<(&str, &str), Uint128> = Map::new("accrued");
+/// The royalties of single magotchis, overriding the royalties of the config
+pub const TOKEN_ROYALTIES: Map<&str, RoyaltyConfig> = Map::new("token_royalties");
+/// The hex encoded commitments of the metadata batches that have not been revealed yet
+pub const REVEAL_COMMITMENTS: Map<&str, Empty> = Map::new("reveal_commitments");
+/// The mystery boxes, minted without metadata and showing the placeholder until they are revealed
+pub const UNREVEALED: Map<&str, Empty> = Map::new("unrevealed");
+/// Stored as (token_id, feeder), keeping track of everyone who took care of a magotchi
+pub const CARETAKERS: Map<(&str, &Addr), Caretaker> = Map::new("caretakers");
+/// The caretakers that contributed the most to each magotchi, ordered by their contribution and
+/// bounded by MAX_TOP_CARETAKERS, so they can be ranked without ranging over all caretakers
+pub const TOP_CARETAKERS: Map<&str, Vec<Addr>> = Map::new("top_caretakers");
+pub const MAX_TOP_CARETAKERS: usize = 20;
+/// Stored as (owner, item), the number of each item an address bought and hasn't used yet
+pub const INVENTORIES: Map<(&Addr, &str), u32> = Map::new("inventories");
+/// The commit-reveal randomness round of every unhatched magotchi, whose seed drives its genes
+pub const RANDOMNESS_ROUNDS: Map<&str, Round> = Map::new("randomness_rounds");
+/// Stored as (token_id, feeder), the addresses the owner allowed to feed a magotchi
+pub const FEEDING_ALLOWLIST: Map<(&str, &Addr), Empty> = Map::new("feeding_allowlist");
+
+/// The graves of reaped magotchis, indexed by their former owner and their time of death
+pub fn graves<'a>() -> IndexedMap<'a, String, Grave, GraveIndexes<'a>> {
+    let indexes = GraveIndexes {
+        former_owner: MultiIndex::new(
+            |_pk, grave: &Grave| grave.former_owner.clone(),
+            "graves",
+            "graves__former_owner",
+        ),
+        died_at: MultiIndex::new(
+            |_pk, grave: &Grave| grave.died_at.seconds(),
+            "graves",
+            "graves__died_at",
+        ),
+    };
+    IndexedMap::new("graves", indexes)
+}
+
+pub struct GraveIndexes<'a> {
+    pub former_owner: MultiIndex<'a, Addr, Grave, String>,
+    pub died_at: MultiIndex<'a, u64, Grave, String>,
+}
+
+impl<'a> IndexList<Grave> for GraveIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Grave>> + '_> {
+        let v: Vec<&dyn Index<Grave>> = vec![&self.former_owner, &self.died_at];
+        Box::new(v.into_iter())
+    }
+}
+
+/// The magotchis listed for sale, indexed by their seller and their price
+pub fn listings<'a>() -> IndexedMap<'a, &'a str, Listing, ListingIndexes<'a>> {
+    let indexes = ListingIndexes {
+        seller: MultiIndex::new(
+            |_pk, listing: &Listing| listing.seller.clone(),
+            "listings",
+            "listings__seller",
+        ),
+        price: MultiIndex::new(
+            |_pk, listing: &Listing| (listing.price.denom.clone(), listing.price.amount.u128()),
+            "listings",
+            "listings__price",
+        ),
+    };
+    IndexedMap::new("listings", indexes)
+}
+
+pub struct ListingIndexes<'a> {
+    pub seller: MultiIndex<'a, Addr, Listing, String>,
+    pub price: MultiIndex<'a, (String, u128), Listing, String>,
+}
+
+impl<'a> IndexList<Listing> for ListingIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Listing>> + '_> {
+        let v: Vec<&dyn Index<Listing>> = vec![&self.seller, &self.price];
+        Box::new(v.into_iter())
+    }
+}
+
+#[cw_serde]
+pub struct Listing {
+    pub seller: Addr,
+    /// the exact price the buyer has to pay
+    pub price: Coin,
+    /// the listing and the approval of the contract to transfer the magotchi expire together
+    pub expires: Expiration,
+}
+
+impl Listing {
+    pub fn status(&self, state: &Gotchi, block: &BlockInfo) -> ListingStatus {
+        if state.is_dead(block) {
+            ListingStatus::Dead
+        } else if self.expires.is_expired(block) {
+            ListingStatus::Expired
+        } else {
+            ListingStatus::Active
+        }
+    }
+}
+
+#[cw_serde]
+pub enum ListingStatus {
+    /// the magotchi can be bought
+    Active,
+    /// the listing expired, the seller has to list the magotchi again
+    Expired,
+    /// the magotchi died, it can't be bought until it is revived
+    Dead,
+}
+
+/// The rentals of magotchis. Like an approval, but the borrower can only care for the magotchi
+pub const RENTALS: Map<&str, Rental> = Map::new("rentals");
+
+#[cw_serde]
+pub struct Rental {
+    /// the borrower, who can feed and care for the magotchi until the rental expires
+    pub user: Approval,
+    /// the price the borrower has to pay the owner to start the rental, unset once it is paid
+    pub price: Option<Coin>,
+}
+
+impl Rental {
+    pub fn is_active(&self, block: &BlockInfo) -> bool {
+        self.price.is_none() && !self.user.is_expired(block)
+    }
+}
+
+/// Returns the borrower of a magotchi, if it is rented out
+pub fn active_user(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    token_id: &str,
+) -> StdResult<Option<Addr>> {
+    Ok(RENTALS
+        .may_load(storage, token_id)?
+        .filter(|rental| rental.is_active(block))
+        .map(|rental| rental.user.spender))
+}
+
+/// The magotchis in auction, which are held by the contract until the auction is settled
+pub const AUCTIONS: Map<&str, Auction> = Map::new("auctions");
+
+#[cw_serde]
+pub struct Auction {
+    pub seller: Addr,
+    /// the minimum first bid, its denom is the denom of all bids
+    pub reserve_price: Coin,
+    /// the minimum amount a bid has to exceed the highest bid by
+    pub min_increment: Uint128,
+    pub end_time: Timestamp,
+    /// the highest bid, which is held by the contract
+    pub highest_bid: Option<Bid>,
+    /// the number of global pauses that have been applied to the end time
+    #[serde(default)]
+    pub pauses_applied: u32,
+}
+
+#[cw_serde]
+pub struct Bid {
+    pub bidder: Addr,
+    pub amount: Coin,
+}
+
+impl Auction {
+    pub fn is_ended(&self, block: &BlockInfo) -> bool {
+        block.time >= self.end_time
+    }
+
+    /// Shifts the end time by the pauses that have not been applied yet. Auctions that already
+    /// ended when a pause started are not affected by it
+    pub fn catch_up_pauses(&mut self, periods: &[PausePeriod]) {
+        for period in periods.iter().skip(self.pauses_applied as usize) {
+            if self.end_time <= period.started_at {
+                continue;
+            }
+
+            let paused_seconds = period.ended_at.seconds() - period.started_at.seconds();
+            self.end_time = self.end_time.plus_seconds(paused_seconds);
+        }
+        self.pauses_applied = periods.len() as u32;
+    }
+
+    /// Returns the minimum amount of the next bid
+    pub fn min_bid(&self) -> Coin {
+        match &self.highest_bid {
+            Some(bid) => Coin {
+                denom: bid.amount.denom.clone(),
+                amount: bid.amount.amount + self.min_increment,
+            },
+            None => self.reserve_price.clone(),
+        }
+    }
+
+    /// Returns whether the auction is cancelled, because the magotchi died and the marketplace
+    /// cancels the auctions of dead magotchis
+    pub fn is_cancelled(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        marketplace: &MarketplaceConfig,
+        token_id: &str,
+    ) -> StdResult<bool> {
+        Ok(marketplace.on_death == AuctionDeathRule::Cancel
+            && load_gotchi(storage, token_id)?.is_dead(block))
+    }
+}
+
+#[cw_serde]
+pub struct Grave {
+    /// the owner of the magotchi at the time it was reaped
+    pub former_owner: Addr,
+    pub died_at: Timestamp,
+    pub reaped_at: Timestamp,
+}
 
 #[cw_serde]
 pub struct Gotchi {
     hatched_at: Option<Timestamp>,
     death_time: Timestamp,
+    #[serde(default)]
+    revivals: u32,
+    #[serde(default)]
+    vacation: Vacation,
+    /// the number of global pauses that have been applied to the death time
+    #[serde(default)]
+    pauses_applied: u32,
+    #[serde(default)]
+    last_bred_at: Option<Timestamp>,
+    #[serde(default)]
+    genome: Option<Genome>,
+    #[serde(default)]
+    care: Care,
+    /// when the magotchi got sick, it loses health faster until it is cured
+    #[serde(default)]
+    sick_since: Option<Timestamp>,
+}
+
+/// The genes of a magotchi, generated when it hatches
+#[cw_serde]
+pub struct Genome {
+    /// the hex encoded hash the traits were selected with
+    pub dna: String,
+    pub traits: Vec<Trait>,
+    /// the rate at which the magotchi gets hungry in promille, shortening or lengthening the max_unfed_days
+    pub hunger_multiplier: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Pause {
+    /// set while the contract is paused
+    pub paused_at: Option<Timestamp>,
+    /// all finished pauses, the death times of the magotchis are shifted by these lazily
+    pub periods: Vec<PausePeriod>,
+}
+
+#[cw_serde]
+pub struct PausePeriod {
+    pub started_at: Timestamp,
+    pub ended_at: Timestamp,
+}
+
+impl Pause {
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Returns the block with its time frozen at the start of the pause, if paused
+    pub fn block(&self, block: &BlockInfo) -> BlockInfo {
+        match self.paused_at {
+            Some(paused_at) => BlockInfo {
+                time: paused_at,
+                ..block.clone()
+            },
+            None => block.clone(),
+        }
+    }
+}
+
+/// Loads the live state of a magotchi, with its death time shifted by the global pauses it missed
+pub fn load_gotchi(storage: &dyn Storage, token_id: &str) -> StdResult<Gotchi> {
+    let mut state = LIVE_STATES.load(storage, token_id.to_string())?;
+    let pause = PAUSE.may_load(storage)?.unwrap_or_default();
+    state.catch_up_pauses(&pause.periods);
+    Ok(state)
+}
+
+/// The last time each care action was done for a magotchi. Until then, its stats decay from the
+/// time it hatched
+#[cw_serde]
+#[derive(Default)]
+pub struct Care {
+    pub played_at: Option<Timestamp>,
+    pub cleaned_at: Option<Timestamp>,
+    pub slept_at: Option<Timestamp>,
+}
+
+/// The care actions beyond feeding, each restoring one of the secondary stats of a magotchi
+#[cw_serde]
+pub enum CareAction {
+    /// restores the happiness
+    Play,
+    /// restores the hygiene
+    Clean,
+    /// restores the energy
+    Sleep,
+}
+
+impl CareAction {
+    pub const ALL: [CareAction; 3] = [CareAction::Play, CareAction::Clean, CareAction::Sleep];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CareAction::Play => "play",
+            CareAction::Clean => "clean",
+            CareAction::Sleep => "sleep",
+        }
+    }
+}
+
+/// Keeps track of the vacations bought for a magotchi, hunger is paused until paused_until
+#[cw_serde]
+#[derive(Default)]
+pub struct Vacation {
+    pub paused_until: Option<Timestamp>,
+    /// the start of the current vacation year, the days_used are reset once it is over
+    pub year_start: Timestamp,
+    pub days_used: u32,
 }
 
 impl Gotchi {
@@ -23,6 +376,13 @@ impl Gotchi {
         Self {
             hatched_at: None,
             death_time: Timestamp::from_nanos(u64::MAX),
+            revivals: 0,
+            vacation: Vacation::default(),
+            pauses_applied: 0,
+            last_bred_at: None,
+            genome: None,
+            care: Care::default(),
+            sick_since: None,
         }
     }
 
@@ -53,10 +413,293 @@ impl Gotchi {
             return Err(ContractError::MagotchiDied {});
         }
 
+        // feeding during a vacation only starts the hunger again once the vacation is over
+        let fed_at = match self.vacation.paused_until {
+            Some(paused_until) if paused_until > block.time => paused_until,
+            _ => block.time,
+        };
+        self.death_time = fed_at.plus_days(max_unfed_days);
+        Ok(self.to_owned())
+    }
+
+    pub fn start_vacation(
+        &mut self,
+        block: &BlockInfo,
+        days: u32,
+        config: &VacationConfig,
+    ) -> CResult<Self> {
+        if !self.is_hatched() {
+            return Err(ContractError::MagotchiUnhatched {});
+        }
+
+        if self.is_dead(block) {
+            return Err(ContractError::MagotchiDied {});
+        }
+
+        if self.is_on_vacation(block) {
+            return Err(ContractError::AlreadyOnVacation {});
+        }
+
+        if days == 0 || days > config.max_days {
+            return Err(ContractError::InvalidVacationDays {
+                max: config.max_days,
+            });
+        }
+
+        let days_used = self.vacation_days_used(block);
+        if days_used + days > config.max_days_per_year {
+            return Err(ContractError::VacationLimitReached {
+                remaining: config.max_days_per_year.saturating_sub(days_used),
+            });
+        }
+
+        if days_used == 0 {
+            self.vacation.year_start = block.time;
+        }
+        self.vacation.days_used = days_used + days;
+        self.vacation.paused_until = Some(block.time.plus_days(days.into()));
+        self.death_time = self.death_time.plus_days(days.into());
+        Ok(self.to_owned())
+    }
+
+    pub fn is_on_vacation(&self, block: &BlockInfo) -> bool {
+        self.vacation
+            .paused_until
+            .map_or(false, |paused_until| paused_until > block.time)
+    }
+
+    pub fn paused_until(&self) -> Option<Timestamp> {
+        self.vacation.paused_until
+    }
+
+    /// Returns the vacation days used in the current vacation year
+    pub fn vacation_days_used(&self, block: &BlockInfo) -> u32 {
+        if block.time >= self.vacation.year_start.plus_days(DAYS_PER_YEAR) {
+            return 0;
+        }
+        self.vacation.days_used
+    }
+
+    /// Extends the time until the magotchi dies by the given days, but not beyond the maximum days
+    /// without food
+    pub fn nourish(&mut self, block: &BlockInfo, days: u64, max_unfed_days: u64) -> CResult<Self> {
+        if !self.is_hatched() {
+            return Err(ContractError::MagotchiUnhatched {});
+        }
+
+        if self.is_dead(block) {
+            return Err(ContractError::MagotchiDied {});
+        }
+
+        let fed_at = match self.vacation.paused_until {
+            Some(paused_until) if paused_until > block.time => paused_until,
+            _ => block.time,
+        };
+        self.death_time = self
+            .death_time
+            .plus_days(days)
+            .min(fed_at.plus_days(max_unfed_days))
+            .max(self.death_time);
+        Ok(self.to_owned())
+    }
+
+    pub fn revive(&mut self, block: &BlockInfo, max_unfed_days: u64) -> CResult<Self> {
+        if !self.is_hatched() {
+            return Err(ContractError::MagotchiUnhatched {});
+        }
+
+        if !self.is_dead(block) {
+            return Err(ContractError::MagotchiNotDead {});
+        }
+
         self.death_time = block.time.plus_days(max_unfed_days);
+        self.revivals += 1;
+        self.sick_since = None;
         Ok(self.to_owned())
     }
 
+    /// Shifts the death time by the pauses that have not been applied yet. Magotchis that were
+    /// unhatched or already dead when a pause started are not affected by it
+    pub fn catch_up_pauses(&mut self, periods: &[PausePeriod]) {
+        for period in periods.iter().skip(self.pauses_applied as usize) {
+            if !self.is_hatched() || self.death_time <= period.started_at {
+                continue;
+            }
+
+            let paused_seconds = period.ended_at.seconds() - period.started_at.seconds();
+            self.death_time = self.death_time.plus_seconds(paused_seconds);
+            if let Some(paused_until) = self.vacation.paused_until {
+                if paused_until > period.started_at {
+                    self.vacation.paused_until = Some(paused_until.plus_seconds(paused_seconds));
+                }
+            }
+        }
+        self.pauses_applied = periods.len() as u32;
+    }
+
+    /// Checks whether the magotchi can breed and starts its breeding cooldown
+    pub fn breed(&mut self, block: &BlockInfo, config: &BreedingConfig) -> CResult<Self> {
+        let hatched_at = self.hatched_at.ok_or(ContractError::MagotchiUnhatched {})?;
+
+        if self.is_dead(block) {
+            return Err(ContractError::MagotchiDied {});
+        }
+
+        if hatched_at.plus_days(config.min_age_days) > block.time {
+            return Err(ContractError::TooYoungToBreed {});
+        }
+
+        if let Some(last_bred_at) = self.last_bred_at {
+            if last_bred_at.plus_days(config.cooldown_days) > block.time {
+                return Err(ContractError::BreedingCooldown {});
+            }
+        }
+
+        self.last_bred_at = Some(block.time);
+        Ok(self.to_owned())
+    }
+
+    /// Does a care action, restoring its stat. The action can't be done again until the cooldown
+    /// is over
+    pub fn care(
+        &mut self,
+        block: &BlockInfo,
+        action: &CareAction,
+        config: &CareActionConfig,
+    ) -> CResult<Self> {
+        if !self.is_hatched() {
+            return Err(ContractError::MagotchiUnhatched {});
+        }
+
+        if self.is_dead(block) {
+            return Err(ContractError::MagotchiDied {});
+        }
+
+        if let Some(cared_at) = self.cared_at(action) {
+            let ready_at = cared_at.plus_seconds(config.cooldown);
+            if ready_at > block.time {
+                return Err(ContractError::CareCooldown { ready_at });
+            }
+        }
+
+        let cared_at = match action {
+            CareAction::Play => &mut self.care.played_at,
+            CareAction::Clean => &mut self.care.cleaned_at,
+            CareAction::Sleep => &mut self.care.slept_at,
+        };
+        *cared_at = Some(block.time);
+        Ok(self.to_owned())
+    }
+
+    pub fn cared_at(&self, action: &CareAction) -> Option<Timestamp> {
+        match action {
+            CareAction::Play => self.care.played_at,
+            CareAction::Clean => self.care.cleaned_at,
+            CareAction::Sleep => self.care.slept_at,
+        }
+    }
+
+    /// Returns the stat of a care action, from 100 right after the action down to 0
+    pub fn stat(&self, block: &BlockInfo, action: &CareAction, config: &CareActionConfig) -> u32 {
+        let Some(since) = self.cared_at(action).or(self.hatched_at) else {
+            return MAX_STAT;
+        };
+        let elapsed = block.time.seconds().saturating_sub(since.seconds());
+        let decay = elapsed.saturating_mul(config.decay_per_day as u64) / (24 * 60 * 60);
+        MAX_STAT.saturating_sub(decay.min(MAX_STAT as u64) as u32)
+    }
+
+    /// Shortens the death time by the neglect of the secondary stats, but never to less than a
+    /// day from now
+    pub fn neglect(&mut self, block: &BlockInfo, config: &CareConfig) {
+        let penalty: u64 = CareAction::ALL
+            .iter()
+            .map(|action| {
+                let action_config = config.get(action);
+                (MAX_STAT - self.stat(block, action, action_config)) as u64
+                    * action_config.neglect_weight
+            })
+            .sum();
+        let earliest = block.time.plus_days(1).min(self.death_time);
+        let shortened = Timestamp::from_nanos(
+            self.death_time
+                .nanos()
+                .saturating_sub(penalty.saturating_mul(1_000_000_000)),
+        );
+        self.death_time = shortened.max(earliest);
+    }
+
+    /// Rolls whether a hungry magotchi gets sick, with the block and the token_id as entropy. A
+    /// sick magotchi loses the rest of its health faster, until it is cured. Returns whether it
+    /// got sick
+    pub fn roll_sickness(
+        &mut self,
+        block: &BlockInfo,
+        token_id: &str,
+        config: &SicknessConfig,
+        max_unfed_days: u64,
+    ) -> bool {
+        if self.is_sick()
+            || !self.is_hatched()
+            || self.is_dead(block)
+            || self.days_unfed(block, max_unfed_days) < config.min_days_unfed
+        {
+            return false;
+        }
+
+        let hash = Sha256::new()
+            .chain_update(block.time.nanos().to_be_bytes())
+            .chain_update(block.height.to_be_bytes())
+            .chain_update(token_id.as_bytes())
+            .finalize();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hash[..8]);
+        if u64::from_be_bytes(bytes) % 1000 >= config.chance {
+            return false;
+        }
+
+        let remaining = (self.death_time.nanos() - block.time.nanos()) as u128;
+        // a sick magotchi dies sooner, but never right away
+        let shortened = (remaining * 1000 / config.multiplier.max(1) as u128).max(1);
+        self.death_time = block.time.plus_nanos(shortened as u64);
+        self.sick_since = Some(block.time);
+        true
+    }
+
+    pub fn cure(&mut self) {
+        self.sick_since = None;
+    }
+
+    pub fn is_sick(&self) -> bool {
+        self.sick_since.is_some()
+    }
+
+    pub fn sick_since(&self) -> Option<Timestamp> {
+        self.sick_since
+    }
+
+    pub fn genome(&self) -> Option<&Genome> {
+        self.genome.as_ref()
+    }
+
+    pub fn set_genome(&mut self, genome: Genome) {
+        self.genome = Some(genome);
+    }
+
+    pub fn hunger_multiplier(&self) -> u64 {
+        self.genome
+            .as_ref()
+            .map_or(1000, |genome| genome.hunger_multiplier)
+    }
+
+    pub fn last_bred_at(&self) -> Option<Timestamp> {
+        self.last_bred_at
+    }
+
+    pub fn revivals(&self) -> u32 {
+        self.revivals
+    }
+
     pub fn hatched_at(&self) -> Option<Timestamp> {
         self.hatched_at
     }
@@ -77,11 +720,17 @@ impl Gotchi {
         if self.is_dead(block) {
             return max_unfed_days;
         }
+
+        // a magotchi on vacation is treated as freshly fed
+        if self.is_on_vacation(block) {
+            return 0;
+        }
         let days_until_dead = self.days_until_dead(block);
 
-        (max_unfed_days - days_until_dead)
-            .checked_sub(1)
-            .unwrap_or(0)
+        // the max can shrink below the days until dead when the magotchi gets sick
+        max_unfed_days
+            .saturating_sub(days_until_dead)
+            .saturating_sub(1)
     }
 
     pub fn health(&self, block: &BlockInfo, max_unfed_days: u64) -> u32 {
@@ -105,11 +754,386 @@ pub struct Config {
     pub feeding_cost_multiplier: u64,
     /// the cost of hatching a magotchi
     pub graveyard: Addr,
+    /// if set, part of every feeding payment is paid back to the long-term caretakers of the magotchi
+    pub caretaker_rewards: Option<CaretakerRewards>,
+    /// who is allowed to feed a magotchi
+    #[serde(default)]
+    pub feeding_policy: FeedingPolicy,
+    /// if set, hungry magotchis can only be transferred after paying their feeding cost, which
+    /// can be attached to the transfer
+    #[serde(default)]
+    pub hunger_tax: bool,
+    /// if set, dead magotchis can be revived at a premium
+    pub revival: Option<RevivalConfig>,
+    /// if set, owners can buy vacations that pause the hunger of their magotchi
+    pub vacation: Option<VacationConfig>,
+    /// if set, two adult magotchis can be bred to mint an egg
+    pub breeding: Option<BreedingConfig>,
+    /// if set, magotchis get random traits when they hatch
+    pub genetics: Option<GeneticsConfig>,
+    /// if set, anyone can mint a magotchi for the configured price
+    pub public_mint: Option<PublicMintConfig>,
+    /// if set, the addresses in the merkle tree can mint a magotchi before the public mint
+    pub presale: Option<PresaleConfig>,
+    /// if set, all revenue is tracked in a ledger and split between the recipients on withdraw
+    pub treasury: Option<TreasuryConfig>,
+    /// if set, secondary sales of the magotchis owe royalties, as reported by the CW2981 queries
+    pub royalties: Option<RoyaltyConfig>,
+    /// if set, magotchis minted without metadata are mystery boxes until their metadata is revealed
+    pub reveal: Option<RevealConfig>,
+    /// if set, owners can list their magotchis for sale in the contract
+    pub marketplace: Option<MarketplaceConfig>,
+    /// if set, magotchis can be played with, cleaned and put to sleep, and neglecting them
+    /// shortens their life
+    pub care: Option<CareConfig>,
+    /// the catalog of items that can be bought into the inventory and used on magotchis
+    #[serde(default)]
+    pub items: Vec<ItemConfig>,
+    /// if set, hungry magotchis can get sick and lose health faster until they are cured
+    pub sickness: Option<SicknessConfig>,
+    /// if set, the genes of a magotchi are drawn from a commit-reveal round of the players
+    /// instead of the block it hatches in
+    pub randomness: Option<RandomnessConfig>,
+}
+
+#[cw_serde]
+pub struct RandomnessConfig {
+    /// the seconds commitments are accepted for, after the first commitment of a round
+    pub commit_seconds: u64,
+    /// the seconds the secrets can be revealed for, after the commit phase
+    pub reveal_seconds: u64,
+    /// the number of players that have to reveal their secret for the round to produce a seed.
+    /// With a single player, that player knows the genes in advance
+    pub min_players: u32,
+    /// the bond that must be paid in any of the denoms with a commitment. It is refunded when
+    /// the secret is revealed and kept as revenue otherwise. It is required, so filling a round
+    /// with players isn't free
+    pub bond: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct SicknessConfig {
+    /// the days a magotchi has to go unfed before it can get sick
+    pub min_days_unfed: u64,
+    /// the chance of getting sick every time a hungry magotchi is touched, in promille
+    pub chance: u64,
+    /// the rate at which a sick magotchi loses health in promille, at least 1000
+    pub multiplier: u64,
+}
+
+#[cw_serde]
+pub struct ItemConfig {
+    /// the unique name of the item, e.g. "snack" or "revive_potion"
+    pub name: String,
+    /// the price of one item in any of the denoms
+    pub price: Vec<Coin>,
+    pub effect: ItemEffect,
+}
+
+#[cw_serde]
+pub enum ItemEffect {
+    /// extends the time until the magotchi dies by the given days, up to the maximum days
+    /// without food
+    Nourish { days: u64 },
+    /// feeds the magotchi fully, restoring its health and curing its sickness
+    Heal {},
+    /// revives a dead magotchi, counting towards the maximum revivals
+    Revive {},
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct CareConfig {
+    pub play: CareActionConfig,
+    pub clean: CareActionConfig,
+    pub sleep: CareActionConfig,
+}
+
+impl CareConfig {
+    pub fn get(&self, action: &CareAction) -> &CareActionConfig {
+        match action {
+            CareAction::Play => &self.play,
+            CareAction::Clean => &self.clean,
+            CareAction::Sleep => &self.sleep,
+        }
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct CareActionConfig {
+    /// the cost of the action in any of the denoms, the action is free if empty
+    pub cost: Vec<Coin>,
+    /// the seconds until the action can be done again
+    pub cooldown: u64,
+    /// the points the stat decays by per day, out of 100
+    pub decay_per_day: u32,
+    /// the seconds the death time is shortened by for every point the stat is below 100 when the
+    /// magotchi is fed
+    pub neglect_weight: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct MarketplaceConfig {
+    /// the share of every sale in promille that is kept as revenue of the contract
+    pub protocol_fee: u64,
+    /// bids within this many seconds of the end of an auction extend it to this many seconds
+    #[serde(default)]
+    pub anti_snipe_seconds: u64,
+    /// what happens to the auction of a magotchi that dies before it ends
+    #[serde(default)]
+    pub on_death: AuctionDeathRule,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum AuctionDeathRule {
+    /// the auction is cancelled, the magotchi is returned to the seller and the bid is refunded
+    #[default]
+    Cancel,
+    /// the auction continues, the highest bidder gets the dead magotchi and can revive it
+    Continue,
+}
+
+#[cw_serde]
+pub struct RevealConfig {
+    /// the metadata shown for mystery boxes until they are revealed
+    pub placeholder: Metadata,
+}
+
+/// A batch of metadata that is committed to before it is revealed
+#[cw_serde]
+pub struct RevealBatch {
+    pub token_ids: Vec<String>,
+    pub metadata: Vec<Metadata>,
+}
+
+impl RevealBatch {
+    /// Returns the commitment of the batch, the hex encoded sha256 hash of its json
+    pub fn commitment(&self) -> StdResult<String> {
+        Ok(hex::encode(Sha256::digest(to_json_vec(self)?)))
+    }
+}
+
+#[cw_serde]
+pub struct RoyaltyConfig {
+    /// the address the royalties are paid to
+    pub payment_address: Addr,
+    /// the royalties in percent of the sale price
+    pub percentage: u64,
+}
+
+impl RoyaltyConfig {
+    /// Returns the royalties of a magotchi, the royalties of the token if they are overridden,
+    /// otherwise the royalties of the config
+    pub fn load(storage: &dyn Storage, token_id: &str) -> StdResult<Option<Self>> {
+        match TOKEN_ROYALTIES.may_load(storage, token_id)? {
+            Some(royalties) => Ok(Some(royalties)),
+            None => Ok(CONFIG.load(storage)?.royalties),
+        }
+    }
+
+    /// Returns the royalties owed for the given sale price
+    pub fn amount(&self, sale_price: Uint128) -> Uint128 {
+        sale_price.multiply_ratio(self.percentage, 100u64)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.percentage <= 100 && self.payment_address != Addr::unchecked("")
+    }
+
+    /// Whether the royalties and the protocol fee of the marketplace add up to at most 100%
+    pub fn fits_protocol_fee(&self, marketplace: Option<&MarketplaceConfig>) -> bool {
+        marketplace.map_or(true, |marketplace| {
+            self.percentage
+                .saturating_mul(10)
+                .saturating_add(marketplace.protocol_fee)
+                <= 1000
+        })
+    }
+}
+
+#[cw_serde]
+pub struct TreasuryConfig {
+    /// the shares of the splits must add up to 1000 promille
+    pub splits: Vec<RevenueSplit>,
+}
+
+#[cw_serde]
+pub struct RevenueSplit {
+    pub recipient: Recipient,
+    /// the share of the revenue in promille
+    pub share: u64,
+}
+
+#[cw_serde]
+pub enum Recipient {
+    /// an address, like the team or a community pool contract, paid out on WithdrawFunds
+    Address { address: Addr },
+    /// paid out to whoever reaps the next dead magotchis
+    Reapers {},
+}
+
+impl Recipient {
+    /// Returns the key of the recipient in the ledger
+    pub fn key(&self) -> String {
+        match self {
+            Recipient::Address { address } => address.to_string(),
+            Recipient::Reapers {} => "reapers".to_string(),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PresaleConfig {
+    /// the hex encoded sha256 root of the merkle tree of eligible addresses
+    pub merkle_root: String,
+    /// the presale price of a single magotchi, payable in any of the given denoms
+    pub price: Vec<Coin>,
+    /// the presale stops once it minted this many tokens
+    pub max_supply: u64,
+    /// the maximum number of magotchis a single eligible address can mint
+    pub per_address_limit: u32,
+    pub start_time: Option<Timestamp>,
+    pub end_time: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct PublicMintConfig {
+    /// the price of a single magotchi, payable in any of the given denoms
+    pub price: Vec<Coin>,
+    /// the public mint stops once it minted this many tokens
+    pub max_supply: u64,
+    /// the maximum number of magotchis a single address can mint
+    pub per_address_limit: u32,
+    pub start_time: Option<Timestamp>,
+    pub end_time: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct GeneticsConfig {
+    /// a trait is picked from every table when a magotchi hatches
+    pub traits: Vec<TraitTable>,
+}
+
+#[cw_serde]
+pub struct TraitTable {
+    /// the trait_type of the picked trait, e.g. color, species or temperament
+    pub trait_type: String,
+    pub options: Vec<WeightedTrait>,
+}
+
+#[cw_serde]
+pub struct WeightedTrait {
+    pub value: String,
+    /// the chance of this value is its weight divided by the total weight of the table
+    pub weight: u32,
+    /// the hunger rate of magotchis with this trait in promille, 1000 if not set
+    pub hunger_multiplier: Option<u64>,
+}
+
+#[cw_serde]
+pub struct BreedingConfig {
+    /// the fee for breeding, payable in any of the given denoms
+    pub fee: Vec<Coin>,
+    /// the minimum number of days since hatching before a magotchi can breed
+    pub min_age_days: u64,
+    /// the number of days a magotchi has to wait before it can breed again
+    pub cooldown_days: u64,
+}
+
+impl BreedingConfig {
+    pub fn get_fee(&self, denom: &str) -> CResult<Coin> {
+        self.fee
+            .iter()
+            .find(|coin| coin.denom == denom)
+            .cloned()
+            .ok_or(ContractError::InvalidDenom {
+                denom: denom.to_string(),
+            })
+    }
+}
+
+#[cw_serde]
+pub struct VacationConfig {
+    /// the price of a single vacation day
+    pub price_per_day: Vec<Coin>,
+    /// the maximum length of a single vacation in days
+    pub max_days: u32,
+    /// the maximum number of vacation days per magotchi per year
+    pub max_days_per_year: u32,
+}
+
+impl VacationConfig {
+    pub fn get_cost(&self, days: u32, denom: &str) -> CResult<Coin> {
+        let price = self
+            .price_per_day
+            .iter()
+            .find(|coin| coin.denom == denom)
+            .ok_or(ContractError::InvalidDenom {
+                denom: denom.to_string(),
+            })?
+            .amount
+            .saturating_mul(Uint128::from(days));
+
+        Ok(Coin {
+            denom: denom.to_string(),
+            amount: price,
+        })
+    }
+}
+
+#[cw_serde]
+pub struct RevivalConfig {
+    /// the cost of a revival, as a multiple of the cost of feeding a magotchi that went max_unfed_days without food
+    pub cost_multiplier: u64,
+    /// the maximum number of times a single magotchi can be revived, unlimited if not set
+    pub max_revivals: Option<u32>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum FeedingPolicy {
+    /// anyone can feed a magotchi
+    #[default]
+    Open,
+    /// only the owner of a magotchi and the addresses approved to send it can feed it
+    OwnerAndApproved,
+    /// the owner, approved addresses and the addresses on the allowlist of a magotchi can feed it
+    Allowlist,
+}
+
+#[cw_serde]
+pub struct CaretakerRewards {
+    /// the share of each feeding payment in promille that is split between the long-term caretakers
+    pub share: u64,
+    /// the minimum number of feedings before a caretaker is considered a long-term caretaker
+    pub min_feedings: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Caretaker {
+    /// the number of times the caretaker fed the magotchi
+    pub feedings: u64,
+    /// the total feeding cost paid by the caretaker, in units of the daily feeding cost
+    pub contribution: u64,
 }
 
 impl Config {
+    /// Returns the max_unfed_days of the magotchi, adjusted by its hunger rate and sickness
+    pub fn max_unfed_days_for(&self, state: &Gotchi) -> u64 {
+        let max_unfed_days = self.max_unfed_days as u64 * 1000 / state.hunger_multiplier().max(1);
+        match &self.sickness {
+            Some(sickness) if state.is_sick() => max_unfed_days * 1000 / sickness.multiplier.max(1),
+            _ => max_unfed_days,
+        }
+        .max(1)
+    }
+
     pub fn get_feeding_cost(&self, state: &Gotchi, block: &BlockInfo) -> u64 {
-        let days_unfed = state.days_unfed(block, self.max_unfed_days as u64);
+        let days_unfed = state.days_unfed(block, self.max_unfed_days_for(state));
 
         let total = calculate_total_cost(days_unfed, self.feeding_cost_multiplier);
         return total;
@@ -122,7 +1146,29 @@ impl Config {
         denom: &str,
     ) -> CResult<Coin> {
         let cost = self.get_feeding_cost(state, block);
+        self.get_price(cost, denom)
+    }
+
+    pub fn get_revival_cost(&self, revival: &RevivalConfig, denom: &str) -> CResult<Coin> {
+        let max_feeding_cost =
+            calculate_total_cost(self.max_unfed_days as u64, self.feeding_cost_multiplier);
+        self.get_price(
+            max_feeding_cost.saturating_mul(revival.cost_multiplier),
+            denom,
+        )
+    }
 
+    /// Returns the price of the given units of the daily feeding cost in the given denom
+    pub fn get_item(&self, name: &str) -> CResult<&ItemConfig> {
+        self.items
+            .iter()
+            .find(|item| item.name == name)
+            .ok_or(ContractError::UnknownItem {
+                item: name.to_string(),
+            })
+    }
+
+    fn get_price(&self, cost: u64, denom: &str) -> CResult<Coin> {
         let feeding_price = self
             .daily_feeding_cost
             .iter()
@@ -147,6 +1193,68 @@ impl Config {
                 .iter()
                 .any(|coin| coin.amount.is_zero())
             && self.graveyard != Addr::unchecked("")
+            && self
+                .caretaker_rewards
+                .as_ref()
+                .map_or(true, |rewards| rewards.share <= 1000)
+            && self.vacation.as_ref().map_or(true, |vacation| {
+                !vacation.price_per_day.is_empty()
+                    && !vacation
+                        .price_per_day
+                        .iter()
+                        .any(|coin| coin.amount.is_zero())
+                    && vacation.max_days <= vacation.max_days_per_year
+            })
+            && self.genetics.as_ref().map_or(true, |genetics| {
+                genetics.traits.iter().all(|table| {
+                    table.options.iter().any(|option| option.weight > 0)
+                        && table.options.iter().all(|option| {
+                            option.hunger_multiplier.map_or(true, |multiplier| {
+                                multiplier > 0 && multiplier <= MAX_HUNGER_MULTIPLIER
+                            })
+                        })
+                })
+            })
+            && self.public_mint.as_ref().map_or(true, |public_mint| {
+                !public_mint.price.is_empty()
+                    && !public_mint.price.iter().any(|coin| coin.amount.is_zero())
+            })
+            && self.presale.as_ref().map_or(true, |presale| {
+                decode_hash(&presale.merkle_root).is_some()
+                    && !presale.price.is_empty()
+                    && !presale.price.iter().any(|coin| coin.amount.is_zero())
+            })
+            && self.treasury.as_ref().map_or(true, |treasury| {
+                treasury.splits.iter().map(|split| split.share).sum::<u64>() == 1000
+            })
+            && self.royalties.as_ref().map_or(true, |royalties| {
+                royalties.is_valid() && royalties.fits_protocol_fee(self.marketplace.as_ref())
+            })
+            && self
+                .marketplace
+                .as_ref()
+                .map_or(true, |marketplace| marketplace.protocol_fee <= 1000)
+            && self.items.iter().enumerate().all(|(index, item)| {
+                !item.name.is_empty()
+                    && !self.items[..index]
+                        .iter()
+                        .any(|other| other.name == item.name)
+                    && !item.price.is_empty()
+                    && !item.price.iter().any(|coin| coin.amount.is_zero())
+                    && item.effect != ItemEffect::Nourish { days: 0 }
+            })
+            && self.sickness.as_ref().map_or(true, |sickness| {
+                sickness.min_days_unfed > 0
+                    && sickness.chance <= 1000
+                    && sickness.multiplier >= 1000
+            })
+            && self.randomness.as_ref().map_or(true, |randomness| {
+                randomness.commit_seconds > 0
+                    && randomness.reveal_seconds > 0
+                    && randomness.min_players > 0
+                    && !randomness.bond.is_empty()
+                    && !randomness.bond.iter().any(|coin| coin.amount.is_zero())
+            })
         {
             Ok(())
         } else {
@@ -163,6 +1271,23 @@ impl Default for Config {
             max_unfed_days: 10,
             feeding_cost_multiplier: 1,
             graveyard: Addr::unchecked("graveyard"),
+            caretaker_rewards: None,
+            feeding_policy: FeedingPolicy::Open,
+            hunger_tax: false,
+            revival: None,
+            vacation: None,
+            breeding: None,
+            genetics: None,
+            public_mint: None,
+            presale: None,
+            treasury: None,
+            royalties: None,
+            reveal: None,
+            marketplace: None,
+            care: None,
+            items: vec![],
+            sickness: None,
+            randomness: None,
         }
     }
 }
@@ -173,6 +1298,13 @@ impl Gotchi {
         Self {
             hatched_at: None,
             death_time: Timestamp::from_nanos(u64::MAX),
+            revivals: 0,
+            vacation: Vacation::default(),
+            pauses_applied: 0,
+            last_bred_at: None,
+            genome: None,
+            care: Care::default(),
+            sick_since: None,
         }
     }
 
@@ -180,6 +1312,13 @@ impl Gotchi {
         Self {
             hatched_at: Some(Timestamp::default().plus_days(days_since_epoch)),
             death_time: Timestamp::default().plus_days(days_since_epoch + 1),
+            revivals: 0,
+            vacation: Vacation::default(),
+            pauses_applied: 0,
+            last_bred_at: None,
+            genome: None,
+            care: Care::default(),
+            sick_since: None,
         }
     }
 
@@ -187,6 +1326,13 @@ impl Gotchi {
         Self {
             hatched_at: Some(Timestamp::default().plus_days(hatched_at_days_from_epoch)),
             death_time: Timestamp::default().plus_days(death_time_days_from_epoch),
+            revivals: 0,
+            vacation: Vacation::default(),
+            pauses_applied: 0,
+            last_bred_at: None,
+            genome: None,
+            care: Care::default(),
+            sick_since: None,
         }
     }
 
@@ -199,6 +1345,13 @@ impl Gotchi {
             death_time: Timestamp::default()
                 .plus_days(death_time_days_from_epoch)
                 .minus_seconds(1),
+            revivals: 0,
+            vacation: Vacation::default(),
+            pauses_applied: 0,
+            last_bred_at: None,
+            genome: None,
+            care: Care::default(),
+            sick_since: None,
         }
     }
 }
@@ -324,6 +1477,151 @@ mod tests {
                 .is_equal_to(Gotchi::custom(0, 4));
         }
 
+        fn vacation_config() -> VacationConfig {
+            VacationConfig {
+                price_per_day: vec![Coin::new(1_000, "unewt")],
+                max_days: 7,
+                max_days_per_year: 10,
+            }
+        }
+
+        #[test]
+        fn start_vacation() {
+            let config = vacation_config();
+            let mut state = Gotchi::new();
+            assert_that!(state.start_vacation(&mock_block(0), 5, &config))
+                .is_err()
+                .is_equal_to(ContractError::MagotchiUnhatched {});
+
+            state = state.hatch(&mock_block(0)).unwrap();
+            state = state.feed(&mock_block(0), 10).unwrap();
+            assert_that!(state.start_vacation(&mock_block(0), 0, &config))
+                .is_err()
+                .is_equal_to(ContractError::InvalidVacationDays { max: 7 });
+            assert_that!(state.start_vacation(&mock_block(0), 8, &config))
+                .is_err()
+                .is_equal_to(ContractError::InvalidVacationDays { max: 7 });
+
+            state = state.start_vacation(&mock_block(2), 5, &config).unwrap();
+            assert_that!(state.is_on_vacation(&mock_block(6))).is_true();
+            assert_that!(state.is_on_vacation(&mock_block(7))).is_false();
+            assert_that!(state.paused_until()).is_equal_to(Some(mock_block(7).time));
+            assert_that!(state.death_time()).is_equal_to(mock_block(15).time);
+            assert_that!(state.vacation_days_used(&mock_block(7))).is_equal_to(5);
+
+            // treated as freshly fed while on vacation
+            assert_that!(state.days_unfed(&mock_block(6), 10)).is_equal_to(0);
+            assert_that!(state.health(&mock_block(6), 10)).is_equal_to(10);
+            // hunger continues where it was paused
+            assert_that!(state.days_unfed(&mock_block(9), 10)).is_equal_to(3);
+
+            assert_that!(state.start_vacation(&mock_block(3), 1, &config))
+                .is_err()
+                .is_equal_to(ContractError::AlreadyOnVacation {});
+            assert_that!(state.start_vacation(&mock_block(8), 6, &config))
+                .is_err()
+                .is_equal_to(ContractError::VacationLimitReached { remaining: 5 });
+
+            // the yearly limit resets after a year
+            state.death_time = mock_block(380).time;
+            state = state.start_vacation(&mock_block(370), 7, &config).unwrap();
+            assert_that!(state.vacation_days_used(&mock_block(370))).is_equal_to(7);
+        }
+
+        #[test]
+        fn feed_on_vacation() {
+            let mut state = Gotchi::with_hatched_at(0);
+            state = state
+                .start_vacation(&mock_block(0), 5, &vacation_config())
+                .unwrap();
+            state = state.feed(&mock_block(1), 10).unwrap();
+            assert_that!(state.death_time()).is_equal_to(mock_block(15).time);
+        }
+
+        #[test]
+        fn catch_up_pauses() {
+            let periods = vec![
+                PausePeriod {
+                    started_at: mock_block(2).time,
+                    ended_at: mock_block(5).time,
+                },
+                PausePeriod {
+                    started_at: mock_block(20).time,
+                    ended_at: mock_block(21).time,
+                },
+            ];
+
+            let mut state = Gotchi::custom(0, 10);
+            state.catch_up_pauses(&periods[..1]);
+            assert_that!(state.death_time()).is_equal_to(mock_block(13).time);
+            // pauses are only applied once, and the magotchi died before the second one
+            state.catch_up_pauses(&periods);
+            assert_that!(state.death_time()).is_equal_to(mock_block(13).time);
+
+            // dead before the pause
+            let mut state = Gotchi::custom(0, 1);
+            state.catch_up_pauses(&periods);
+            assert_that!(state.death_time()).is_equal_to(mock_block(1).time);
+
+            // unhatched magotchis only keep track of the applied pauses
+            let mut state = Gotchi::new();
+            state.catch_up_pauses(&periods);
+            state = state.hatch(&mock_block(30)).unwrap();
+            state.catch_up_pauses(&periods);
+            assert_that!(state.death_time()).is_equal_to(mock_block(31).time);
+        }
+
+        #[test]
+        fn breed() {
+            let config = BreedingConfig {
+                fee: vec![Coin::new(1_000, "unewt")],
+                min_age_days: 3,
+                cooldown_days: 5,
+            };
+            let mut state = Gotchi::new();
+            assert_that!(state.breed(&mock_block(0), &config))
+                .is_err()
+                .is_equal_to(ContractError::MagotchiUnhatched {});
+
+            state = Gotchi::custom(0, 20);
+            assert_that!(state.breed(&mock_block(2), &config))
+                .is_err()
+                .is_equal_to(ContractError::TooYoungToBreed {});
+
+            state = state.breed(&mock_block(3), &config).unwrap();
+            assert_that!(state.last_bred_at()).is_equal_to(Some(mock_block(3).time));
+            assert_that!(state.breed(&mock_block(7), &config))
+                .is_err()
+                .is_equal_to(ContractError::BreedingCooldown {});
+            assert_that!(state.breed(&mock_block(8), &config)).is_ok();
+
+            assert_that!(state.breed(&mock_block(20), &config))
+                .is_err()
+                .is_equal_to(ContractError::MagotchiDied {});
+        }
+
+        #[test]
+        fn revive() {
+            let mut state = Gotchi::new();
+
+            // unhatched
+            assert_that!(state.revive(&mock_block(0), 10))
+                .is_err()
+                .is_equal_to(ContractError::MagotchiUnhatched {});
+
+            state = state.hatch(&mock_block(0)).unwrap();
+            // alive
+            assert_that!(state.revive(&mock_block(0), 10))
+                .is_err()
+                .is_equal_to(ContractError::MagotchiNotDead {});
+
+            // dead
+            state = state.revive(&mock_block(1), 10).unwrap();
+            assert_that!(state.is_dead(&mock_block(1))).is_false();
+            assert_that!(state.death_time()).is_equal_to(Timestamp::default().plus_days(11));
+            assert_that!(state.revivals()).is_equal_to(1);
+        }
+
         #[test]
         fn hatched_at() {
             let state = Gotchi::new();
@@ -464,6 +1762,7 @@ mod tests {
                 max_unfed_days: 10,
                 feeding_cost_multiplier: 0,
                 graveyard: Addr::unchecked("graveyard"),
+                ..Config::default()
             };
 
             let state = Gotchi::custom_min_1sec(0, 10);
@@ -481,6 +1780,7 @@ mod tests {
                 max_unfed_days: 10,
                 feeding_cost_multiplier: 100,
                 graveyard: Addr::unchecked("graveyard"),
+                ..Config::default()
             };
 
             assert_that!(&config.get_feeding_cost(&state, &mock_block(0))).is_equal_to(0);
@@ -495,6 +1795,7 @@ mod tests {
                 max_unfed_days: 10,
                 feeding_cost_multiplier: 0,
                 graveyard: Addr::unchecked("graveyard"),
+                ..Config::default()
             };
 
             let state = Gotchi::custom_min_1sec(0, 10);
@@ -516,6 +1817,39 @@ mod tests {
                 .is_equal_to(Coin::new(10_000_000, "unewt"));
         }
 
+        #[test]
+        fn get_revival_cost() {
+            let config = Config {
+                daily_feeding_cost: vec![Coin::new(1_000, "unewt")],
+                max_unfed_days: 10,
+                feeding_cost_multiplier: 0,
+                ..Config::default()
+            };
+            let revival = RevivalConfig {
+                cost_multiplier: 3,
+                max_revivals: None,
+            };
+
+            assert_that!(&config.get_revival_cost(&revival, "unewt"))
+                .is_ok()
+                .is_equal_to(Coin::new(30_000_000, "unewt"));
+            assert_that!(&config.get_revival_cost(&revival, "uluna")).is_err();
+        }
+
+        #[test]
+        fn max_unfed_days_for() {
+            let config = Config::default();
+            let mut state = Gotchi::new();
+            assert_that!(config.max_unfed_days_for(&state)).is_equal_to(10);
+
+            state.set_genome(Genome {
+                dna: "00".to_string(),
+                traits: vec![],
+                hunger_multiplier: 2000,
+            });
+            assert_that!(config.max_unfed_days_for(&state)).is_equal_to(5);
+        }
+
         #[test]
         fn validate() {
             let mut config = Config {
@@ -523,6 +1857,7 @@ mod tests {
                 max_unfed_days: 10,
                 feeding_cost_multiplier: 1,
                 graveyard: Addr::unchecked("graveyard"),
+                ..Config::default()
             };
             assert_that!(&config.validate()).is_err();
             config.daily_feeding_cost = vec![Coin::new(0, "unewt")];
@@ -550,6 +1885,337 @@ mod tests {
             assert_that!(&config.validate()).is_err();
             config.graveyard = Addr::unchecked("graveyard");
             assert_that!(&config.validate()).is_ok();
+
+            let genetics = |hunger_multiplier| GeneticsConfig {
+                traits: vec![TraitTable {
+                    trait_type: "species".to_string(),
+                    options: vec![WeightedTrait {
+                        value: "newt".to_string(),
+                        weight: 1,
+                        hunger_multiplier,
+                    }],
+                }],
+            };
+            config.genetics = Some(genetics(Some(0)));
+            assert_that!(&config.validate()).is_err();
+            config.genetics = Some(genetics(Some(MAX_HUNGER_MULTIPLIER + 1)));
+            assert_that!(&config.validate()).is_err();
+            config.genetics = Some(genetics(Some(MAX_HUNGER_MULTIPLIER)));
+            assert_that!(&config.validate()).is_ok();
+            config.genetics = None;
+
+            config.caretaker_rewards = Some(CaretakerRewards {
+                share: 1001,
+                min_feedings: 1,
+            });
+            assert_that!(&config.validate()).is_err();
+            config.caretaker_rewards = Some(CaretakerRewards {
+                share: 1000,
+                min_feedings: 1,
+            });
+            assert_that!(&config.validate()).is_ok();
+
+            config.vacation = Some(VacationConfig {
+                price_per_day: vec![],
+                max_days: 7,
+                max_days_per_year: 30,
+            });
+            assert_that!(&config.validate()).is_err();
+            config.vacation = Some(VacationConfig {
+                price_per_day: vec![Coin::new(1, "unewt")],
+                max_days: 31,
+                max_days_per_year: 30,
+            });
+            assert_that!(&config.validate()).is_err();
+            config.vacation = Some(VacationConfig {
+                price_per_day: vec![Coin::new(1, "unewt")],
+                max_days: 7,
+                max_days_per_year: 30,
+            });
+            assert_that!(&config.validate()).is_ok();
+
+            config.presale = Some(PresaleConfig {
+                merkle_root: "not a root".to_string(),
+                price: vec![Coin::new(1, "unewt")],
+                max_supply: 100,
+                per_address_limit: 1,
+                start_time: None,
+                end_time: None,
+            });
+            assert_that!(&config.validate()).is_err();
+            config.presale = Some(PresaleConfig {
+                merkle_root: "ab".repeat(32),
+                price: vec![Coin::new(0, "unewt")],
+                max_supply: 100,
+                per_address_limit: 1,
+                start_time: None,
+                end_time: None,
+            });
+            assert_that!(&config.validate()).is_err();
+            config.presale = Some(PresaleConfig {
+                merkle_root: "ab".repeat(32),
+                price: vec![Coin::new(1, "unewt")],
+                max_supply: 100,
+                per_address_limit: 1,
+                start_time: None,
+                end_time: None,
+            });
+            assert_that!(&config.validate()).is_ok();
+
+            let public_mint = |price| PublicMintConfig {
+                price,
+                max_supply: 100,
+                per_address_limit: 1,
+                start_time: None,
+                end_time: None,
+            };
+            config.public_mint = Some(public_mint(vec![]));
+            assert_that!(&config.validate()).is_err();
+            config.public_mint = Some(public_mint(vec![Coin::new(0, "unewt")]));
+            assert_that!(&config.validate()).is_err();
+            config.public_mint = Some(public_mint(vec![Coin::new(1, "unewt")]));
+            assert_that!(&config.validate()).is_ok();
+
+            let split = |share| RevenueSplit {
+                recipient: Recipient::Reapers {},
+                share,
+            };
+            config.treasury = Some(TreasuryConfig {
+                splits: vec![split(600), split(300)],
+            });
+            assert_that!(&config.validate()).is_err();
+            config.treasury = Some(TreasuryConfig {
+                splits: vec![split(600), split(400)],
+            });
+            assert_that!(&config.validate()).is_ok();
+
+            config.royalties = Some(RoyaltyConfig {
+                payment_address: Addr::unchecked("artist"),
+                percentage: 101,
+            });
+            assert_that!(&config.validate()).is_err();
+            config.royalties = Some(RoyaltyConfig {
+                payment_address: Addr::unchecked("artist"),
+                percentage: 5,
+            });
+            assert_that!(&config.validate()).is_ok();
+            config.marketplace = Some(MarketplaceConfig {
+                protocol_fee: 960,
+                ..Default::default()
+            });
+            assert_that!(&config.validate()).is_err();
+            config.marketplace = Some(MarketplaceConfig {
+                protocol_fee: 950,
+                ..Default::default()
+            });
+            assert_that!(&config.validate()).is_ok();
+            config.marketplace = None;
+
+            let item = |name: &str, effect| ItemConfig {
+                name: name.to_string(),
+                price: vec![Coin::new(1, "unewt")],
+                effect,
+            };
+            config.items = vec![item("snack", ItemEffect::Nourish { days: 0 })];
+            assert_that!(&config.validate()).is_err();
+            config.items = vec![
+                item("snack", ItemEffect::Nourish { days: 1 }),
+                item("snack", ItemEffect::Heal {}),
+            ];
+            assert_that!(&config.validate()).is_err();
+            config.items = vec![
+                item("snack", ItemEffect::Nourish { days: 1 }),
+                item("medicine", ItemEffect::Heal {}),
+            ];
+            assert_that!(&config.validate()).is_ok();
+
+            config.sickness = Some(SicknessConfig {
+                min_days_unfed: 3,
+                chance: 1001,
+                multiplier: 2000,
+            });
+            assert_that!(&config.validate()).is_err();
+            config.sickness = Some(SicknessConfig {
+                min_days_unfed: 3,
+                chance: 100,
+                multiplier: 999,
+            });
+            assert_that!(&config.validate()).is_err();
+            config.sickness = Some(SicknessConfig {
+                min_days_unfed: 3,
+                chance: 100,
+                multiplier: 2000,
+            });
+            assert_that!(&config.validate()).is_ok();
+
+            let randomness = |commit_seconds, min_players| RandomnessConfig {
+                commit_seconds,
+                reveal_seconds: 60,
+                bond: vec![Coin::new(100, "unewt")],
+                min_players,
+            };
+            config.randomness = Some(randomness(0, 2));
+            assert_that!(&config.validate()).is_err();
+            config.randomness = Some(randomness(60, 0));
+            assert_that!(&config.validate()).is_err();
+            config.randomness = Some(RandomnessConfig {
+                bond: vec![],
+                ..randomness(60, 2)
+            });
+            assert_that!(&config.validate()).is_err();
+            config.randomness = Some(randomness(60, 2));
+            assert_that!(&config.validate()).is_ok();
+        }
+
+        #[test]
+        fn test_royalty_amount() {
+            let royalties = RoyaltyConfig {
+                payment_address: Addr::unchecked("artist"),
+                percentage: 5,
+            };
+            assert_that!(royalties.amount(Uint128::new(1_000_000)))
+                .is_equal_to(Uint128::new(50_000));
+            assert_that!(royalties.amount(Uint128::new(19))).is_equal_to(Uint128::zero());
+        }
+    }
+
+    mod care {
+        use super::*;
+
+        fn care_config() -> CareConfig {
+            CareConfig {
+                play: CareActionConfig {
+                    cost: vec![],
+                    cooldown: ONE_DAY,
+                    decay_per_day: 50,
+                    neglect_weight: 3600,
+                },
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn stats_decay_since_the_last_care() {
+            let config = care_config();
+            let mut state = Gotchi::custom(1, 11);
+
+            // until the first care action, the stats decay from the hatching
+            assert_that!(state.stat(&mock_block(1), &CareAction::Play, &config.play))
+                .is_equal_to(100);
+            assert_that!(state.stat(&mock_block(2), &CareAction::Play, &config.play))
+                .is_equal_to(50);
+            assert_that!(state.stat(&mock_block(4), &CareAction::Play, &config.play))
+                .is_equal_to(0);
+            assert_that!(state.stat(&mock_block(4), &CareAction::Sleep, &config.sleep))
+                .is_equal_to(100);
+
+            state
+                .care(&mock_block(4), &CareAction::Play, &config.play)
+                .unwrap();
+            assert_that!(state.stat(&mock_block(4), &CareAction::Play, &config.play))
+                .is_equal_to(100);
+            assert_that!(state.care(&mock_block_plus1(4), &CareAction::Play, &config.play))
+                .is_err()
+                .is_equal_to(ContractError::CareCooldown {
+                    ready_at: mock_block(5).time,
+                });
+            assert_that!(state.care(&mock_block(5), &CareAction::Play, &config.play)).is_ok();
+        }
+
+        #[test]
+        fn neglect_shortens_the_death_time() {
+            let config = care_config();
+
+            // every point of happiness lost shortens the life by an hour
+            let mut state = Gotchi::custom(1, 11);
+            state.neglect(&mock_block(2), &config);
+            assert_that!(state.death_time())
+                .is_equal_to(Timestamp::from_seconds(ONE_DAY * 11 - 50 * 3600));
+
+            // but never to less than a day
+            let mut state = Gotchi::custom(1, 11);
+            state.neglect(&mock_block(9), &config);
+            assert_that!(state.death_time()).is_equal_to(mock_block(10).time);
+        }
+    }
+
+    mod sickness {
+        use super::*;
+
+        fn sickness_config(chance: u64) -> SicknessConfig {
+            SicknessConfig {
+                min_days_unfed: 3,
+                chance,
+                multiplier: 2000,
+            }
+        }
+
+        #[test]
+        fn only_hungry_magotchis_get_sick() {
+            let config = sickness_config(1000);
+            let mut state = Gotchi::custom(1, 11);
+
+            assert_that!(state.roll_sickness(&mock_block(2), "magotchi1", &config, 10)).is_false();
+            assert_that!(state.is_sick()).is_false();
+
+            // 3 days unfed, the rest of its life is halved
+            assert_that!(state.roll_sickness(&mock_block(5), "magotchi1", &config, 10)).is_true();
+            assert_that!(state.sick_since()).is_equal_to(Some(mock_block(5).time));
+            assert_that!(state.death_time()).is_equal_to(mock_block(8).time);
+
+            // a sick magotchi doesn't get sick again
+            assert_that!(state.roll_sickness(&mock_block(6), "magotchi1", &config, 10)).is_false();
+            assert_that!(state.death_time()).is_equal_to(mock_block(8).time);
+
+            state.cure();
+            assert_that!(state.is_sick()).is_false();
+        }
+
+        #[test]
+        fn sickness_depends_on_the_chance() {
+            let mut state = Gotchi::custom(1, 11);
+            assert_that!(state.roll_sickness(&mock_block(5), "magotchi1", &sickness_config(0), 10))
+                .is_false();
+
+            let mut dead = Gotchi::custom(1, 11);
+            assert_that!(dead.roll_sickness(
+                &mock_block(12),
+                "magotchi1",
+                &sickness_config(1000),
+                10
+            ))
+            .is_false();
+
+            let mut unhatched = Gotchi::new();
+            assert_that!(unhatched.roll_sickness(
+                &mock_block(5),
+                "magotchi1",
+                &sickness_config(1000),
+                10
+            ))
+            .is_false();
+        }
+
+        #[test]
+        fn sick_magotchis_lose_health_faster() {
+            let config = Config {
+                sickness: Some(sickness_config(1000)),
+                ..Config::default()
+            };
+            let mut state = Gotchi::custom(1, 11);
+            assert_that!(config.max_unfed_days_for(&state)).is_equal_to(10);
+
+            state.roll_sickness(
+                &mock_block(5),
+                "magotchi1",
+                config.sickness.as_ref().unwrap(),
+                10,
+            );
+            assert_that!(config.max_unfed_days_for(&state)).is_equal_to(5);
+            assert_that!(state.health(&mock_block(5), 5)).is_equal_to(4);
+
+            state.revive(&mock_block(9), 10).unwrap();
+            assert_that!(state.is_sick()).is_false();
         }
     }
 }
@@ -0,0 +1,3 @@
+mod query;
+
+pub use crate::query::{CheckRoyaltiesResponse, Cw2981QueryMsg, RoyaltiesInfoResponse};
@@ -0,0 +1,35 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
+use cw_orch::QueryFns;
+
+/// The royalty queries, to be exposed through the query extension of a cw721 contract
+#[cw_serde]
+#[derive(QueryFns, QueryResponses)]
+pub enum Cw2981QueryMsg {
+    /// Should be called on sale to see if royalties are owed by the marketplace selling the NFT,
+    /// if CheckRoyalties returns true.
+    /// See https://eips.ethereum.org/EIPS/eip-2981
+    #[returns(RoyaltiesInfoResponse)]
+    RoyaltyInfo {
+        token_id: String,
+        /// the denom of this sale must also be the denom returned by RoyaltiesInfoResponse
+        sale_price: Uint128,
+    },
+    /// Called against the contract to determine if this NFT implements royalties
+    #[returns(CheckRoyaltiesResponse)]
+    CheckRoyalties {},
+}
+
+#[cw_serde]
+pub struct RoyaltiesInfoResponse {
+    /// The address to pay the royalties to, empty if no royalties are owed
+    pub address: String,
+    /// The royalty amount, in the denom of the sale
+    pub royalty_amount: Uint128,
+}
+
+/// Shows if the contract implements royalties
+#[cw_serde]
+pub struct CheckRoyaltiesResponse {
+    pub royalty_payments: bool,
+}